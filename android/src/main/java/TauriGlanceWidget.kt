@@ -52,6 +52,8 @@ import androidx.glance.layout.height
 import androidx.glance.layout.padding
 import androidx.glance.layout.size
 import androidx.glance.layout.width
+import androidx.glance.semantics.contentDescription
+import androidx.glance.semantics.semantics
 import androidx.glance.text.Text
 import androidx.glance.text.TextAlign
 import androidx.glance.text.TextStyle
@@ -61,10 +63,12 @@ import androidx.datastore.preferences.core.Preferences
 import androidx.datastore.preferences.core.stringPreferencesKey
 import org.json.JSONArray
 import org.json.JSONObject
+import org.json.JSONTokener
 import java.text.SimpleDateFormat
 import java.util.Date
 import java.util.Locale
 import java.util.TimeZone
+import java.util.UUID
 
 private const val GLANCE_CONTAINER_LIMIT = 10
 private const val META_PREFS = "__tauri_widget_meta__"
@@ -72,6 +76,19 @@ private const val KEY_ACTIVE_GROUP = "active_group"
 private const val CONFIG_STATE_KEY_NAME = "__widget_config_state__"
 private const val NONCE_STATE_KEY_NAME = "__widget_nonce_state__"
 private const val TAG = "TauriGlanceWidget"
+
+// Layouts tried for each size family, mirroring `WidgetFamily::fallbacks` in
+// the Rust models.  Home screen size buckets only produce the system families.
+private val LAYOUT_KEYS = listOf(
+    "small", "medium", "large", "extraLarge",
+    "accessoryCircular", "accessoryRectangular", "accessoryInline",
+)
+private val LAYOUT_FALLBACKS = mapOf(
+    "small" to listOf("small", "medium", "large", "extraLarge"),
+    "medium" to listOf("medium", "large", "small", "extraLarge"),
+    "large" to listOf("large", "extraLarge", "medium", "small"),
+    "extraLarge" to listOf("extraLarge", "large", "medium", "small"),
+)
 private val BASE64_CACHE = LinkedHashMap<Int, Bitmap>(64, 0.75f, true)
 private val CONFIG_STATE_KEY = stringPreferencesKey(CONFIG_STATE_KEY_NAME)
 private val NONCE_STATE_KEY = stringPreferencesKey(NONCE_STATE_KEY_NAME)
@@ -103,6 +120,7 @@ class TauriGlanceWidget : GlanceAppWidget() {
         val minWidth = options.getInt(AppWidgetManager.OPTION_APPWIDGET_MIN_WIDTH, 0)
         val minHeight = options.getInt(AppWidgetManager.OPTION_APPWIDGET_MIN_HEIGHT, 0)
         return when {
+            minWidth >= 560 && minHeight >= 260 -> "extraLarge"
             minWidth >= 320 && minHeight >= 260 -> "large"
             minWidth >= 180 && minHeight >= 110 -> "medium"
             else -> "small"
@@ -134,8 +152,11 @@ class TauriGlanceWidget : GlanceAppWidget() {
 }
 
 private val ACTION_KEY = ActionParameters.Key<String>("action")
+/** The element payload as JSON text, so objects and numbers keep their type. */
 private val PAYLOAD_KEY = ActionParameters.Key<String>("payload")
 private val URL_KEY = ActionParameters.Key<String>("url")
+private val ELEMENT_ID_KEY = ActionParameters.Key<String>("elementId")
+private val KIND_KEY = ActionParameters.Key<String>("kind")
 
 class WidgetActionCallback : ActionCallback {
     override suspend fun onAction(
@@ -145,7 +166,9 @@ class WidgetActionCallback : ActionCallback {
     ) {
         val group = resolveGroup(context)
         val action = parameters[ACTION_KEY]
-        val payload = parameters[PAYLOAD_KEY]
+        val payload = parameters[PAYLOAD_KEY]?.takeIf { it.isNotEmpty() }?.let {
+            runCatching { JSONTokener(it).nextValue() }.getOrNull()
+        }
         val url = parameters[URL_KEY]
         val normalizedUrl = url?.trim().orEmpty().let {
             if (it.equals("null", ignoreCase = true) || it.equals("undefined", ignoreCase = true)) "" else it
@@ -154,26 +177,32 @@ class WidgetActionCallback : ActionCallback {
         // Action-driven buttons are the primary flow in widgets.
         // Only attempt URL open when no action is provided.
         if (!action.isNullOrBlank()) {
-            val toggled = applyLocalListToggleIfNeeded(context, glanceId, group, action)
-            if (toggled) {
-                Log.d(TAG, "action local list toggle applied action=$action")
+            // A bound toggle reports its new state as the payload.
+            val flipped = flipBoundToggleIfNeeded(context, glanceId, group, action)
+            val eventPayload = flipped?.toString() ?: payload
+            val toggled = applyLocalStateIfNeeded(context, glanceId, group, action, payload as? String)
+            if (toggled || flipped != null) {
+                Log.d(TAG, "action local state applied action=$action")
             }
+            // Same fields as the Rust `WidgetActionEvent`.
+            val event = JSONObject()
+            event.put("action", action)
+            event.put("nonce", UUID.randomUUID().toString())
+            if (eventPayload != null) event.put("payload", eventPayload)
+            parameters[ELEMENT_ID_KEY]?.takeIf { it.isNotEmpty() }?.let { event.put("elementId", it) }
+            parameters[KIND_KEY]?.takeIf { it.isNotEmpty() }?.let { event.put("kind", it) }
+            event.put("group", group)
+            event.put("timestamp", System.currentTimeMillis())
+
             val prefs = context.getSharedPreferences(group, Context.MODE_PRIVATE)
             val pendingRaw = prefs.getString("__widget_pending_actions__", "[]") ?: "[]"
             val arr = runCatching { JSONArray(pendingRaw) }.getOrElse { JSONArray() }
-            if (!payload.isNullOrBlank()) {
-                val obj = JSONObject()
-                obj.put("action", action)
-                obj.put("payload", payload)
-                arr.put(obj)
-            } else {
-                arr.put(action)
-            }
+            arr.put(event)
             prefs.edit().putString("__widget_pending_actions__", arr.toString()).apply()
 
             val plugin = WidgetBridgePlugin.pluginInstance
             if (plugin != null) {
-                plugin.emitWidgetAction(action, payload)
+                plugin.emitWidgetAction(event)
             }
         } else if (normalizedUrl.isNotBlank()) {
             val uri = Uri.parse(normalizedUrl)
@@ -193,18 +222,26 @@ class WidgetActionCallback : ActionCallback {
             .onFailure { e -> Log.e(TAG, "action-triggered update failed=${e.message}", e) }
     }
 
-    private suspend fun applyLocalListToggleIfNeeded(
+    /**
+     * Toggles list items and selects segmented options that match the
+     * tapped action, so the widget reflects the tap before the app responds.
+     */
+    private suspend fun applyLocalStateIfNeeded(
         context: Context,
         glanceId: androidx.glance.GlanceId,
         group: String,
         action: String,
+        payload: String?,
     ): Boolean {
         val prefs = context.getSharedPreferences(group, Context.MODE_PRIVATE)
         val raw = prefs.getString("__widget_config__", null) ?: return false
         val root = runCatching { JSONObject(raw) }.getOrNull() ?: return false
         var changed = false
-        listOf("small", "medium", "large").forEach { key ->
+        LAYOUT_KEYS.forEach { key ->
             changed = toggleListItemsInElement(root.optJSONObject(key), action) || changed
+            if (!payload.isNullOrEmpty()) {
+                changed = selectSegmentInElement(root.optJSONObject(key), action, payload) || changed
+            }
         }
         if (!changed) return false
         val updated = root.toString()
@@ -247,6 +284,83 @@ class WidgetActionCallback : ActionCallback {
         return changed
     }
 
+    /**
+     * Flips the toggle bound to a store key that emits [action]: writes the
+     * key and `isOn` of every toggle bound to it.  Returns the new state, or
+     * null when no bound toggle emits the action.
+     */
+    private suspend fun flipBoundToggleIfNeeded(
+        context: Context,
+        glanceId: androidx.glance.GlanceId,
+        group: String,
+        action: String,
+    ): Boolean? {
+        val prefs = context.getSharedPreferences(group, Context.MODE_PRIVATE)
+        val raw = prefs.getString("__widget_config__", null) ?: return null
+        val root = runCatching { JSONObject(raw) }.getOrNull() ?: return null
+        val toggle = LAYOUT_KEYS.firstNotNullOfOrNull { findBoundToggle(root.optJSONObject(it), action) } ?: return null
+        val key = toggle.getString("key")
+        val current = when (prefs.getString(key, null)?.trim()) {
+            "true", "1" -> true
+            "false", "0" -> false
+            else -> toggle.optBoolean("isOn", false)
+        }
+        val isOn = !current
+        LAYOUT_KEYS.forEach { setBoundToggles(root.optJSONObject(it), key, isOn) }
+        val updated = root.toString()
+        prefs.edit().putString(key, isOn.toString()).putString("__widget_config__", updated).apply()
+        runCatching {
+            updateAppWidgetState(context, glanceId) { state ->
+                state[CONFIG_STATE_KEY] = updated
+                state[NONCE_STATE_KEY] = System.currentTimeMillis().toString()
+            }
+        }.onFailure { e ->
+            Log.e(TAG, "local toggle update state failed=${e.message}", e)
+        }
+        return isOn
+    }
+
+    private fun findBoundToggle(el: JSONObject?, action: String): JSONObject? {
+        if (el == null) return null
+        if (el.optString("type", "") == "toggle" && el.optString("action", "") == action && el.has("key")) return el
+        val children = el.optJSONArray("children") ?: return null
+        for (i in 0 until children.length()) {
+            findBoundToggle(children.optJSONObject(i), action)?.let { return it }
+        }
+        return null
+    }
+
+    private fun setBoundToggles(el: JSONObject?, key: String, isOn: Boolean) {
+        if (el == null) return
+        if (el.optString("type", "") == "toggle" && el.optString("key", "") == key) el.put("isOn", isOn)
+        val children = el.optJSONArray("children") ?: return
+        for (i in 0 until children.length()) setBoundToggles(children.optJSONObject(i), key, isOn)
+    }
+
+    private fun selectSegmentInElement(el: JSONObject?, action: String, value: String): Boolean {
+        if (el == null) return false
+        var changed = false
+        val type = el.optString("type", "")
+        if ((type == "segmented" || type == "picker") && el.optString("action", "") == action) {
+            val options = el.optJSONArray("options") ?: JSONArray()
+            for (i in 0 until options.length()) {
+                if (options.optJSONObject(i)?.optString("value") != value) continue
+                if (el.optInt("selectedIndex", -1) != i) {
+                    el.put("selectedIndex", i)
+                    changed = true
+                }
+                break
+            }
+        }
+        val children = el.optJSONArray("children")
+        if (children != null) {
+            for (i in 0 until children.length()) {
+                changed = selectSegmentInElement(children.optJSONObject(i), action, value) || changed
+            }
+        }
+        return changed
+    }
+
     private fun resolveGroup(context: Context): String {
         val packageName = context.packageName
         val packageNameHyphen = packageName.replace('_', '-')
@@ -296,11 +410,8 @@ private fun WidgetRoot(context: Context, configRaw: String?, size: String) {
         }
         return
     }
-    val element = when (size) {
-        "large" -> config.optJSONObject("large") ?: config.optJSONObject("medium") ?: config.optJSONObject("small")
-        "medium" -> config.optJSONObject("medium") ?: config.optJSONObject("large") ?: config.optJSONObject("small")
-        else -> config.optJSONObject("small") ?: config.optJSONObject("medium") ?: config.optJSONObject("large")
-    }
+    val element = (LAYOUT_FALLBACKS[size] ?: LAYOUT_FALLBACKS.getValue("small"))
+        .firstNotNullOfOrNull { config.optJSONObject(it) }
     if (element == null) {
         Log.e(TAG, "WidgetRoot no layout branch for size=$size cfgHash=${cfgHash(effectiveConfig)}")
         Box(modifier = GlanceModifier.fillMaxSize().padding(12.dp), contentAlignment = Alignment.Center) {
@@ -408,8 +519,10 @@ private fun RenderElement(
                 }
             }
         }
-        "text", "date", "timer" -> {
+        "text", "richText", "date", "timer" -> {
             val content = when (type) {
+                // Glance text has no per-span styling; spans render as plain text.
+                "richText" -> richTextContent(el)
                 "date" -> formatDateValue(el.optString("date", ""), el.optString("dateStyle", "date"))
                 "timer" -> formatTimerValue(
                     el.optString("targetDate", ""),
@@ -426,7 +539,7 @@ private fun RenderElement(
             if (provider != null) {
                 Image(
                     provider = provider,
-                    contentDescription = el.optString("alt", ""),
+                    contentDescription = imageDescription(el, ""),
                     modifier = imageModifier,
                     contentScale = parseContentScale(el.optString("contentMode", "fit"))
                 )
@@ -447,7 +560,7 @@ private fun RenderElement(
                 if (glyphBitmap != null) {
                     Image(
                         provider = ImageProvider(glyphBitmap),
-                        contentDescription = systemName.ifBlank { "image" },
+                        contentDescription = imageDescription(el, systemName.ifBlank { "image" }),
                         modifier = imageModifier,
                         contentScale = ContentScale.Fit
                     )
@@ -473,7 +586,7 @@ private fun RenderElement(
                 if (bmp != null) {
                     Image(
                         provider = ImageProvider(bmp),
-                        contentDescription = type,
+                        contentDescription = imageDescription(el, type),
                         modifier = GlanceModifier.fillMaxWidth(),
                         contentScale = ContentScale.FillBounds
                     )
@@ -484,9 +597,8 @@ private fun RenderElement(
         }
         "link" -> {
             val action = el.optString("action", "")
-            val payload = el.optString("payload", "")
             val url = el.optString("url", "")
-            val wrapped = applyAction(baseModifier, action, payload, url)
+            val wrapped = applyAction(baseModifier, action, el.opt("payload"), url, el, sizeFamily)
             val children = el.optJSONArray("children")
             if (children != null && children.length() > 0) {
                 Box(modifier = wrapped) {
@@ -502,10 +614,9 @@ private fun RenderElement(
         "button", "toggle" -> {
             val label = el.optString("label", el.optString("content", if (type == "toggle") "toggle" else "button"))
             val action = el.optString("action", "")
-            val payload = el.optString("payload", "")
             val url = el.optString("url", "")
             val bg = resolveColorProvider(context, el.opt("backgroundColor")) ?: resolveColorProvider(context, el.opt("tint"))
-            val actionModifier = applyAction(baseModifier, action, payload, url)
+            val actionModifier = applyAction(baseModifier, action, el.opt("payload"), url, el, sizeFamily)
             val text = if (type == "toggle") "${if (el.optBoolean("isOn", false)) "[x]" else "[ ]"} $label" else label
             val buttonAlignRaw = el.optString("textAlignment", el.optString("alignment", "center"))
             val textElement = JSONObject(el.toString()).apply { put("alignment", buttonAlignRaw) }
@@ -530,6 +641,29 @@ private fun RenderElement(
                 }
             }
         }
+        "segmented", "picker" -> {
+            val options = el.optJSONArray("options") ?: JSONArray()
+            val selected = el.optInt("selectedIndex", -1)
+            val action = el.optString("action", "")
+            val tint = resolveColorProvider(context, el.opt("tint")) ?: ColorProvider(Color(0xFF2196F3))
+            Row(modifier = baseModifier.fillMaxWidth()) {
+                for (i in 0 until options.length()) {
+                    val option = options.optJSONObject(i) ?: continue
+                    val isSelected = i == selected
+                    val segment = JSONObject(el.toString()).apply {
+                        put("alignment", "center")
+                        if (isSelected) put("color", "#FFFFFF")
+                        put("fontWeight", if (isSelected) "semibold" else "regular")
+                    }
+                    var modifier = applyAction(GlanceModifier.defaultWeight(), action, option.optString("value"), "", el, sizeFamily)
+                        .padding(horizontal = 6.dp, vertical = 4.dp)
+                    if (isSelected) modifier = modifier.background(tint)
+                    Box(modifier = modifier, contentAlignment = Alignment.Center) {
+                        renderElementText(context, segment, option.optString("label"))
+                    }
+                }
+            }
+        }
         "divider" -> {
             val line = resolveColorProvider(context, el.opt("color")) ?: ColorProvider(Color.Gray)
             val thickness = el.optDouble("thickness", 1.0).toInt().coerceAtLeast(1).dp
@@ -616,8 +750,7 @@ private fun RenderElement(
                     val hasCheckbox = item.has("checked") || item.has("isOn")
                     val checked = item.optBoolean("checked", false) || item.optBoolean("isOn", false)
                     val action = item.optString("action", "")
-                    val payload = item.optString("payload", "")
-                    val rowMod = applyAction(GlanceModifier.fillMaxWidth(), action, payload, "")
+                    val rowMod = applyAction(GlanceModifier.fillMaxWidth(), action, item.opt("payload"), "", el, sizeFamily)
                     val spaced = if (spacing > 0 && index < itemList.size - 1) rowMod.padding(bottom = spacing.dp) else rowMod
                     val prefix = if (hasCheckbox) (if (checked) "✓ " else "○ ") else ""
                     renderElementText(context, el, "$prefix$text", spaced)
@@ -752,14 +885,28 @@ private fun RowScope.renderChildrenHorizontal(
     }
 }
 
-private fun applyAction(modifier: GlanceModifier, action: String, payload: String, url: String): GlanceModifier {
+private fun applyAction(
+    modifier: GlanceModifier,
+    action: String,
+    payload: Any?,
+    url: String,
+    el: JSONObject,
+    sizeFamily: String,
+): GlanceModifier {
     if (action.isBlank() && url.isBlank()) return modifier
+    val payloadJson = when (payload) {
+        null, JSONObject.NULL -> ""
+        is String -> JSONObject.quote(payload)
+        else -> payload.toString()
+    }
     return modifier.clickable(
         actionRunCallback<WidgetActionCallback>(
             actionParametersOf(
                 ACTION_KEY to action,
-                PAYLOAD_KEY to payload,
-                URL_KEY to url
+                PAYLOAD_KEY to payloadJson,
+                URL_KEY to url,
+                ELEMENT_ID_KEY to el.optString("id", ""),
+                KIND_KEY to sizeFamily
             )
         )
     )
@@ -811,9 +958,26 @@ private fun applyCommonStyle(context: Context, modifier: GlanceModifier, el: JSO
         m = m.cornerRadius(clipRadius.dp)
     }
 
+    val label = el.optString("accessibilityLabel", "")
+    if (label.isNotBlank() && el.optBoolean("accessibilityHidden", false).not()) {
+        val hint = el.optString("accessibilityHint", "")
+        m = m.semantics { contentDescription = if (hint.isBlank()) label else "$label. $hint" }
+    }
+
     return m
 }
 
+private fun richTextContent(el: JSONObject): String {
+    val spans = el.optJSONArray("spans") ?: return ""
+    return (0 until spans.length()).joinToString("") { spans.optJSONObject(it)?.optString("text", "").orEmpty() }
+}
+
+/** Content description for images: `null` (skipped by TalkBack) when hidden. */
+private fun imageDescription(el: JSONObject, fallback: String): String? {
+    if (el.optBoolean("accessibilityHidden", false)) return null
+    return el.optString("accessibilityLabel", "").ifBlank { el.optString("alt", "").ifBlank { fallback } }
+}
+
 private fun resolveBackgroundProvider(context: Context, value: Any?): ColorProvider? {
     if (value == null) return null
     if (value is String) return colorProviderFromString(context, value)
@@ -1049,7 +1213,7 @@ private fun renderChartElement(context: Context, modifier: GlanceModifier, el: J
     if (bmp != null) {
         Image(
             provider = ImageProvider(bmp),
-            contentDescription = "chart",
+            contentDescription = imageDescription(el, "chart"),
             modifier = modifier.fillMaxWidth(),
             contentScale = ContentScale.FillBounds
         )
@@ -1069,7 +1233,7 @@ private fun renderCanvasElement(context: Context, modifier: GlanceModifier, el:
         if (heightDp > 0) imageModifier = imageModifier.height(heightDp.dp)
         Image(
             provider = ImageProvider(bmp),
-            contentDescription = "canvas",
+            contentDescription = imageDescription(el, "canvas"),
             modifier = imageModifier,
             contentScale = ContentScale.FillBounds
         )
@@ -1124,7 +1288,10 @@ private fun sparkline(values: List<Double>): String {
 }
 
 private fun drawChartBitmap(context: Context, el: JSONObject): Bitmap? {
-    val data = el.optJSONArray("chartData") ?: return null
+    // Multi-series charts are drawn as their first series.
+    val data = el.optJSONArray("chartData")?.takeIf { it.length() > 0 }
+        ?: el.optJSONArray("series")?.optJSONObject(0)?.optJSONArray("data")
+        ?: return null
     if (data.length() == 0) return null
 
     val points = mutableListOf<Pair<Float, Int>>()
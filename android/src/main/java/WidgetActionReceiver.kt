@@ -4,6 +4,8 @@ import android.content.BroadcastReceiver
 import android.content.Context
 import android.content.Intent
 import org.json.JSONArray
+import org.json.JSONObject
+import java.util.UUID
 
 class WidgetActionReceiver : BroadcastReceiver() {
     companion object {
@@ -19,23 +21,23 @@ class WidgetActionReceiver : BroadcastReceiver() {
         val group = WidgetSanitizer.sanitizeGroup(groupRaw, context.packageName)
         val payload = intent.getStringExtra(WidgetBridgePlugin.EXTRA_EVENT_PAYLOAD)
 
+        val event = JSONObject()
+        event.put("action", actionName)
+        event.put("nonce", UUID.randomUUID().toString())
+        if (payload != null) event.put("payload", payload)
+        event.put("group", group)
+        event.put("timestamp", System.currentTimeMillis())
+
         val plugin = WidgetBridgePlugin.pluginInstance
         if (plugin != null) {
-            plugin.emitWidgetAction(actionName, payload)
+            plugin.emitWidgetAction(event)
             return
         }
 
         val prefs = context.getSharedPreferences(group, Context.MODE_PRIVATE)
         val existing = prefs.getString(PENDING_KEY, "[]") ?: "[]"
         val arr = try { JSONArray(existing) } catch (_: Exception) { JSONArray() }
-        if (payload != null) {
-            val obj = org.json.JSONObject()
-            obj.put("action", actionName)
-            obj.put("payload", payload)
-            arr.put(obj)
-        } else {
-            arr.put(actionName)
-        }
+        arr.put(event)
         prefs.edit().putString(PENDING_KEY, arr.toString()).apply()
     }
 }
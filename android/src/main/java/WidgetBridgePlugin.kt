@@ -214,10 +214,9 @@ class WidgetBridgePlugin(private val activity: Activity) : Plugin(activity) {
         pluginInstance = this
     }
 
-    fun emitWidgetAction(action: String, payload: String?) {
-        val event = JSObject()
-        event.put("action", action)
-        event.put("payload", payload)
+    /** Emit a tap, given with the fields of the Rust `WidgetActionEvent`. */
+    fun emitWidgetAction(data: org.json.JSONObject) {
+        val event = JSObject(data.toString())
         runCatching {
             activity.runOnUiThread { trigger("widget-action", event) }
         }.onFailure {
@@ -444,27 +443,14 @@ class WidgetBridgePlugin(private val activity: Activity) : Plugin(activity) {
             val out = org.json.JSONArray()
             if (arr.length() > 0) {
                 for (i in 0 until arr.length()) {
-                    val item = arr.opt(i)
-                    var actionName: String? = null
-                    var payload: String? = null
-                    if (item is org.json.JSONObject) {
-                        actionName = if (item.has("action") && !item.isNull("action")) {
-                            item.optString("action", "")
-                        } else {
-                            null
-                        }
-                        payload = if (item.has("payload") && !item.isNull("payload")) {
-                            item.optString("payload", "")
-                        } else {
-                            null
-                        }
-                    } else if (item is String) {
-                        actionName = item
+                    // Objects keep their payload type and metadata; bare
+                    // strings are action names from older widgets.
+                    val event = when (val item = arr.opt(i)) {
+                        is org.json.JSONObject -> item
+                        is String -> org.json.JSONObject().put("action", item)
+                        else -> null
                     }
-                    if (actionName.isNullOrEmpty()) continue
-                    val event = org.json.JSONObject()
-                    event.put("action", actionName)
-                    event.put("payload", payload)
+                    if (event == null || event.optString("action", "").isEmpty()) continue
                     out.put(event)
                 }
                 prefs.edit().putString("__widget_pending_actions__", "[]").apply()
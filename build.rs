@@ -1,6 +1,13 @@
+use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
 
+// The config models only depend on serde/schemars, so the build script can
+// compile them directly to generate the shipped JSON Schema.
+#[allow(dead_code)]
+#[path = "src/models.rs"]
+mod models;
+
 const COMMANDS: &[&str] = &[
     "set_items",
     "get_items",
@@ -12,14 +19,23 @@ const COMMANDS: &[&str] = &[
     "close_widget_window",
     "set_widget_config",
     "get_widget_config",
+    "patch_widget_config",
+    "get_widget_config_schema",
+    "lint_widget_config",
     "widget_action",
     "poll_pending_actions",
+    "ack_actions",
+    "get_action_stats",
+    "open_widget_url",
+    "get_widget_activity",
+    "export_widget_activity",
 ];
 
 fn main() {
     let target = std::env::var("TARGET").unwrap_or_default();
 
     let result = tauri_plugin::Builder::new(COMMANDS)
+        .global_scope_schema(schemars::schema_for!(models::WidgetScope))
         .android_path("android")
         .ios_path("ios")
         .try_build();
@@ -28,6 +44,8 @@ fn main() {
         result.unwrap();
     }
 
+    write_widget_config_schema();
+
     if target.contains("apple-darwin") {
         let sdk = macos_sdk_path();
         let arch = if target.contains("aarch64") {
@@ -39,6 +57,21 @@ fn main() {
     }
 }
 
+/// Regenerate `schemas/widget-config.schema.json` from the Rust models.
+/// Only writes when the content changed, and ignores write failures so
+/// builds from a read-only source (crates.io, docs.rs) still succeed.
+fn write_widget_config_schema() {
+    println!("cargo:rerun-if-changed=src/models.rs");
+    let manifest = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let path = manifest.join("schemas").join("widget-config.schema.json");
+    let schema = schemars::schema_for!(models::WidgetConfig);
+    let json = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+    if fs::read_to_string(&path).ok().as_deref() != Some(json.as_str()) {
+        let _ = fs::create_dir_all(manifest.join("schemas"));
+        let _ = fs::write(&path, json);
+    }
+}
+
 fn compile_reload_bridge(sdk: &str, arch: &str) {
     let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
     let manifest = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
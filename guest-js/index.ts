@@ -37,6 +37,61 @@ import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 const PLUGIN_ID = "plugin:widgets";
 
+// ─── Errors ──────────────────────────────────────────────────────────────────
+
+/**
+ * Stable code of a plugin error. `internal` covers errors without a more
+ * specific code, e.g. a failure of the native bridge.
+ */
+export type WidgetErrorCode =
+  | "io"
+  | "serde"
+  | "unsupported"
+  | "notFound"
+  | "validation"
+  | "permissionDenied"
+  | "throttled"
+  | "internal";
+
+/** One problem in an invalid widget config or patch. */
+export interface WidgetValidationIssue {
+  /** JSON Pointer of the offending value, e.g. `/small/children/0/style`. */
+  path: string;
+  message: string;
+}
+
+/**
+ * Error a plugin command rejects with.
+ *
+ * @example
+ * ```ts
+ * try {
+ *   await setWidgetConfig(config, group);
+ * } catch (e) {
+ *   if (isWidgetError(e, "validation")) {
+ *     for (const issue of e.details ?? []) console.warn(issue.path, issue.message);
+ *   }
+ * }
+ * ```
+ */
+export interface WidgetError {
+  code: WidgetErrorCode;
+  message: string;
+  /** The issues of a `validation` error; `null` otherwise. */
+  details: WidgetValidationIssue[] | null;
+}
+
+/** Whether `e` is a plugin error, optionally with the given `code`. */
+export function isWidgetError(e: unknown, code?: WidgetErrorCode): e is WidgetError {
+  if (!e || typeof e !== "object") return false;
+  const err = e as Partial<WidgetError>;
+  return (
+    typeof err.code === "string" &&
+    typeof err.message === "string" &&
+    (code === undefined || err.code === code)
+  );
+}
+
 // ─── Data API ────────────────────────────────────────────────────────────────
 
 /**
@@ -137,6 +192,29 @@ export async function requestWidget(): Promise<boolean> {
 
 // ─── Widget Window API (desktop only) ───────────────────────────────────────
 
+/**
+ * Widget size family, named after the WidgetKit families.  A widget whose
+ * family has no layout uses the first one found in this order:
+ *
+ * | Family                 | Fallbacks                                    |
+ * |------------------------|----------------------------------------------|
+ * | `small`                | medium, large, extraLarge                    |
+ * | `medium`               | large, small, extraLarge                     |
+ * | `large`                | extraLarge, medium, small                    |
+ * | `extraLarge`           | large, medium, small                         |
+ * | `accessoryCircular`    | accessoryRectangular, small, medium, …       |
+ * | `accessoryRectangular` | accessoryCircular, small, medium, …          |
+ * | `accessoryInline`      | accessoryRectangular, accessoryCircular, small, … |
+ */
+export type WidgetFamily =
+  | "small"
+  | "medium"
+  | "large"
+  | "extraLarge"
+  | "accessoryCircular"
+  | "accessoryRectangular"
+  | "accessoryInline";
+
 /** Configuration for creating a desktop widget window. */
 export interface WidgetWindowConfig {
   /** Unique window label (e.g. `"cpu-widget"`). Used to reference the window later. */
@@ -148,10 +226,10 @@ export interface WidgetWindowConfig {
    * you want to use your own custom renderer.
    */
   url?: string;
-  /** Window width in logical pixels. */
-  width: number;
-  /** Window height in logical pixels. */
-  height: number;
+  /** Window width in logical pixels.  Defaults to the width of the `size` family. */
+  width?: number;
+  /** Window height in logical pixels.  Defaults to the height of the `size` family. */
+  height?: number;
   /** X position on screen (pixels from left). */
   x?: number;
   /** Y position on screen (pixels from top). */
@@ -167,10 +245,60 @@ export interface WidgetWindowConfig {
    */
   group?: string;
   /**
-   * Size family the built-in renderer should display:
-   * `"small"`, `"medium"`, or `"large"`.  Defaults to `"small"`.
+   * Size family the built-in renderer should display.  Defaults to
+   * `"small"`; a missing layout falls back to a related family.
    */
-  size?: "small" | "medium" | "large";
+  size?: WidgetFamily;
+  /**
+   * Theme for the built-in renderer.  Overrides the plugin-wide theme
+   * configured on the Rust `Builder`.  Ignored when a custom `url` is set.
+   */
+  theme?: WidgetTheme;
+}
+
+/** A web font registered with `@font-face` in the built-in renderer. */
+export interface ThemeFont {
+  /** Family name used in `font-family` declarations. */
+  family: string;
+  /** Font URL — `https://…`, `data:` URI, or an asset URL. */
+  src: string;
+  /** CSS `font-weight` value or range (e.g. `"400"`, `"100 900"`). */
+  weight?: string;
+  /** CSS `font-style` value (e.g. `"italic"`). */
+  style?: string;
+}
+
+/**
+ * Look and feel of the built-in desktop renderer.
+ *
+ * Recognised CSS variables include `widget-font-family`, `widget-background`,
+ * `widget-color`, `widget-radius`, `widget-drag-height`, `widget-close-size`,
+ * `widget-close-background`, `widget-close-color`,
+ * `widget-close-hover-background`, `widget-close-hover-color`,
+ * `widget-placeholder-background`, `widget-placeholder-color`,
+ * `widget-error-background` and `widget-error-color`.
+ *
+ * @example
+ * ```ts
+ * theme: {
+ *   variables: { "widget-font-family": "Inter, sans-serif" },
+ *   fonts: [{ family: "Inter", src: "https://example.com/inter.woff2" }],
+ *   stylesheet: "#widget-frame { border-radius: 16px; }",
+ *   hideDragHandle: true,
+ * }
+ * ```
+ */
+export interface WidgetTheme {
+  /** CSS custom properties set on `:root`. The leading `--` is optional. */
+  variables?: Record<string, string>;
+  /** Extra CSS appended after the built-in styles. */
+  stylesheet?: string;
+  /** Web fonts to register. */
+  fonts?: ThemeFont[];
+  /** Hide the hover close button. Default: `false`. */
+  hideCloseButton?: boolean;
+  /** Hide the drag region along the top edge. Default: `false`. */
+  hideDragHandle?: boolean;
 }
 
 /**
@@ -184,6 +312,8 @@ export interface WidgetWindowConfig {
  * You may provide your own `url` (e.g. `"/my-widget.html"`) if you
  * need full control over the rendering.
  *
+ * Rejects when a window with the same `label` already exists.
+ *
  * **Desktop only.** Returns an error on mobile.
  *
  * @example
@@ -323,29 +453,98 @@ export type GradientDirection =
  */
 export type ColorValue = string | { light: string; dark: string };
 
+/**
+ * Reference to a design token in `WidgetConfig.tokens`, e.g. `"$accent"`.
+ * Use `"$$"` for a literal leading `$`.
+ */
+export type TokenRef = `$${string}`;
+
+/** A size in points, or a `tokens.spacing` / `tokens.fonts` reference. */
+export type Dimension = number | TokenRef;
+
+/**
+ * Reference to a string in `WidgetConfig.strings`, resolved for the current
+ * locale.  `args` fill `{name}` placeholders; `count` picks the plural form.
+ */
+export interface TextRef {
+  t: string;
+  args?: Record<string, string | number>;
+}
+
+/** User-visible text: a literal or a localized string reference. */
+export type TextValue = string | TextRef;
+
+/** Plural forms keyed by CLDR category; `other` is required. */
+export interface PluralForms {
+  zero?: string;
+  one?: string;
+  two?: string;
+  few?: string;
+  many?: string;
+  other: string;
+}
+
+/** A localized string: plain text or plural forms. */
+export type LocalizedString = string | PluralForms;
+
 /** A single data point in a chart. */
 export interface ChartDataPoint {
   /** X-axis label. */
-  label: string;
+  label: TextValue;
   /** Numeric value. */
   value: number;
   /** Override color for this data point. Falls back to chart `tint`. */
   color?: ColorValue;
 }
 
+/** A named chart series. Series share the x axis and are matched by index. */
+export interface ChartSeries {
+  /** Name shown in the legend. */
+  name?: TextValue;
+  data: ChartDataPoint[];
+  /** Series color. Defaults to a palette color by position. */
+  color?: ColorValue;
+}
+
+/** Labels, gridlines and range of a chart axis. */
+export interface ChartAxis {
+  title?: TextValue;
+  /** Show tick labels. Default: on for the x axis of bar charts, off otherwise. */
+  labels?: boolean;
+  gridlines?: boolean;
+  /** Number of labelled ticks on the y axis. Default: 3. */
+  tickCount?: number;
+  /** Lower bound of the y axis instead of the data minimum (or 0). */
+  min?: number;
+  /** Upper bound of the y axis instead of the data maximum. */
+  max?: number;
+}
+
+/** A horizontal rule line across a chart, e.g. a target or limit. */
+export interface ChartThreshold {
+  value: number;
+  color?: ColorValue;
+  label?: TextValue;
+  /** Draw a dashed line. Default: `true`. */
+  dashed?: boolean;
+}
+
+/** Legend placement. */
+export type LegendPosition = "top" | "bottom" | "hidden";
+
 /**
  * Frame size constraints.
  * Use `"infinity"` for `maxWidth`/`maxHeight` to fill available space.
  */
 export interface FrameConfig {
   /** Fixed width in points. */
-  width?: number;
+  width?: Dimension;
   /** Fixed height in points. */
-  height?: number;
+  height?: Dimension;
   /** Maximum width. Use `"infinity"` to expand. */
-  maxWidth?: number | "infinity";
+  maxWidth?: Dimension | "infinity";
   /** Maximum height. Use `"infinity"` to expand. */
-  maxHeight?: number | "infinity";
+  maxHeight?: Dimension | "infinity";
 }
 
 /** Border configuration. */
@@ -353,7 +552,7 @@ export interface BorderConfig {
   /** Border color (hex). */
   color: string;
   /** Border width in points. Default: `1`. */
-  width?: number;
+  width?: Dimension;
 }
 
 /** Gradient background configuration. */
@@ -371,7 +570,7 @@ export interface ShadowConfig {
   /** Shadow color (hex). */
   color?: string;
   /** Blur radius in points. */
-  radius?: number;
+  radius?: Dimension;
   /** Horizontal offset. */
   x?: number;
   /** Vertical offset. */
@@ -407,11 +606,11 @@ export type BackgroundValue = string | GradientConfig | { light: string; dark: s
  * padding: { top: 8, bottom: 16, leading: 12, trailing: 12 }
  * ```
  */
-export type PaddingValue = number | {
-  top?: number;
-  bottom?: number;
-  leading?: number;
-  trailing?: number;
+export type PaddingValue = Dimension | {
+  top?: Dimension;
+  bottom?: Dimension;
+  leading?: Dimension;
+  trailing?: Dimension;
 };
 
 /**
@@ -421,12 +620,21 @@ export type PaddingValue = number | {
  * the element-specific properties.
  */
 export interface ElementStyle {
+  /**
+   * Stable element identity.  Lets the desktop renderer keep unchanged
+   * nodes across updates and lets patches address the element.
+   */
+  id?: string;
+  /** Name of an entry in `WidgetConfig.styles` to apply. */
+  style?: string;
+  /** Font token from `WidgetConfig.tokens.fonts` (size, weight and design). */
+  font?: TokenRef;
   /** Padding inside the element. */
   padding?: PaddingValue;
   /** Background — solid hex color, adaptive pair, or gradient config. */
   background?: BackgroundValue;
   /** Corner radius in points. */
-  cornerRadius?: number;
+  cornerRadius?: Dimension;
   /** Opacity from 0.0 (transparent) to 1.0 (opaque). */
   opacity?: number;
   /** Frame size constraints. */
@@ -443,8 +651,35 @@ export interface ElementStyle {
    * Maps to SwiftUI `layoutPriority` / Android `layout_weight`.
    */
   flex?: number;
+  /**
+   * Text read by screen readers instead of the element's content.
+   * Images and icon-only buttons need one (see `lintWidgetConfig`).
+   */
+  accessibilityLabel?: TextValue;
+  /** Describes what happens when the element is activated. */
+  accessibilityHint?: TextValue;
+  /** Hide the element and its children from screen readers (decorative content). */
+  accessibilityHidden?: boolean;
+  /** Semantic role; defaults to the role implied by the element type. */
+  accessibilityRole?: AccessibilityRole;
 }
 
+/**
+ * Semantic role for assistive technologies — accessibility traits on Apple,
+ * semantics on Android, ARIA roles on desktop.  `group` reads an element and
+ * its children as one item; `live` marks content that updates on its own.
+ */
+export type AccessibilityRole =
+  | "header"
+  | "button"
+  | "link"
+  | "image"
+  | "text"
+  | "toggle"
+  | "progress"
+  | "group"
+  | "live";
+
 // ── Element interfaces ──
 
 /**
@@ -459,7 +694,7 @@ export interface VStackElement extends ElementStyle {
   type: "vstack";
   children: WidgetElement[];
   /** Space between children (points). */
-  spacing?: number;
+  spacing?: Dimension;
   /** Horizontal alignment of children. */
   alignment?: HorizontalAlignment;
 }
@@ -476,7 +711,7 @@ export interface HStackElement extends ElementStyle {
   type: "hstack";
   children: WidgetElement[];
   /** Space between children (points). */
-  spacing?: number;
+  spacing?: Dimension;
   /** Vertical alignment of children. */
   alignment?: VerticalAlignment;
 }
@@ -503,9 +738,9 @@ export interface GridElement extends ElementStyle {
   /** Number of columns. Default: `2`. */
   columns?: number;
   /** Column spacing (points). */
-  spacing?: number;
+  spacing?: Dimension;
   /** Row spacing (points). */
-  rowSpacing?: number;
+  rowSpacing?: Dimension;
 }
 
 /**
@@ -551,11 +786,11 @@ export interface ContainerElement extends ElementStyle {
 export interface TextElement extends ElementStyle {
   type: "text";
   /** The text string to display. */
-  content: string;
+  content: TextValue;
   /**
    * Font size in points. Ignored when `textStyle` is set.
    */
-  fontSize?: number;
+  fontSize?: Dimension;
   /** Font weight. */
   fontWeight?: FontWeight;
   /** Font design style. */
@@ -575,6 +810,52 @@ export interface TextElement extends ElementStyle {
   lineLimit?: number;
 }
 
+/** A run of text inside a `richText` element.  Unset properties inherit from the element. */
+export interface TextSpan {
+  text: TextValue;
+  fontWeight?: FontWeight;
+  fontSize?: Dimension;
+  italic?: boolean;
+  strikethrough?: boolean;
+  underline?: boolean;
+  /** Render in a monospaced font, e.g. for inline code. */
+  monospace?: boolean;
+  color?: ColorValue;
+  /** Deep-link URL opened when the span is tapped. */
+  link?: string;
+}
+
+/**
+ * Rich text element — text with per-span styling, given as `spans`, as
+ * `markdown`, or both (markdown spans are appended).  Android widgets show
+ * the spans as plain text.
+ *
+ * @example
+ * ```json
+ * { "type": "richText", "markdown": "**3** new messages from _Alice_", "fontSize": 14 }
+ * ```
+ */
+export interface RichTextElement extends ElementStyle {
+  type: "richText";
+  spans?: TextSpan[];
+  /**
+   * Markdown parsed into spans before the config is stored: `**bold**`,
+   * `*italic*` / `_italic_`, `~~strikethrough~~`, `` `code` `` and
+   * `[link](url)`.  A backslash escapes a marker.
+   */
+  markdown?: TextValue;
+  /** Default font size for spans. Ignored when `textStyle` is set. */
+  fontSize?: Dimension;
+  /** Default font weight for spans. */
+  fontWeight?: FontWeight;
+  fontDesign?: FontDesign;
+  textStyle?: TextStyle;
+  /** Default color for spans. */
+  color?: ColorValue;
+  alignment?: TextAlignment;
+  lineLimit?: number;
+}
+
 /**
  * Image element — displays an SF Symbol, base64 data, or remote URL.
  *
@@ -592,7 +873,7 @@ export interface ImageElement extends ElementStyle {
   /** Remote image URL. */
   url?: string;
   /** Display size in points. */
-  size?: number;
+  size?: Dimension;
   /** Tint color — hex, semantic, or adaptive. */
   color?: ColorValue;
   /** How the image fills its frame. */
@@ -614,7 +895,7 @@ export interface ProgressElement extends ElementStyle {
   /** Maximum value. Default: `1.0`. */
   total?: number;
   /** Label text shown alongside the progress bar. */
-  label?: string;
+  label?: TextValue;
   /** Bar color — hex, semantic, or adaptive. */
   tint?: ColorValue;
   /** Text color for label — hex, semantic, or adaptive. */
@@ -645,9 +926,9 @@ export interface GaugeElement extends ElementStyle {
   /** Maximum value. Default: `1`. */
   max?: number;
   /** Caption below the gauge. */
-  label?: string;
+  label?: TextValue;
   /** Value label displayed inside the gauge. */
-  currentValueLabel?: string;
+  currentValueLabel?: TextValue;
   /** Gauge color — hex, semantic, or adaptive. */
   tint?: ColorValue;
   /** Text color for value/label — hex, semantic, or adaptive. */
@@ -656,6 +937,18 @@ export interface GaugeElement extends ElementStyle {
   gaugeStyle?: GaugeStyle;
 }
 
+/**
+ * Action payload — any JSON value. String payloads, including JSON encoded
+ * in a string, are delivered unchanged.
+ */
+export type ActionPayload =
+  | string
+  | number
+  | boolean
+  | null
+  | ActionPayload[]
+  | { [key: string]: ActionPayload };
+
 /**
  * Button — tappable element that opens a URL or triggers an action event.
  *
@@ -675,17 +968,19 @@ export interface GaugeElement extends ElementStyle {
 export interface ButtonElement extends ElementStyle {
   type: "button";
   /** Button text. */
-  label: string;
+  label: TextValue;
   /** Deep-link URL to open when tapped. */
   url?: string;
   /** Action identifier — emits a `widget-action` event when tapped. */
   action?: string;
+  /** Sent with `action` as the event payload. */
+  payload?: ActionPayload;
   /** Text color — hex, semantic, or adaptive. */
   color?: ColorValue;
   /** Background color — hex, semantic, or adaptive. */
   backgroundColor?: ColorValue;
   /** Font size in points. */
-  fontSize?: number;
+  fontSize?: Dimension;
   /** Button label alignment. */
   textAlignment?: TextAlignment;
 }
@@ -703,13 +998,21 @@ export interface ToggleElement extends ElementStyle {
   /** Whether the toggle is on. */
   isOn: boolean;
   /** Label text next to the toggle. */
-  label?: string;
+  label?: TextValue;
   /** Toggle tint color when on — hex, semantic, or adaptive. */
   tint?: ColorValue;
   /** Label text color — hex, semantic, or adaptive. */
   color?: ColorValue;
-  /** Action identifier sent back to the app (for future interactivity). */
+  /** Action identifier sent back to the app when tapped. */
   action?: string;
+  /** Sent with `action`. Bound toggles send their new state instead. */
+  payload?: ActionPayload;
+  /**
+   * Store key holding the state as `"true"` or `"false"`. When set, the
+   * plugin flips the key and `isOn` on tap and sends the new state as the
+   * action payload; `isOn` is only the initial state.
+   */
+  key?: string;
 }
 
 /**
@@ -725,7 +1028,7 @@ export interface DividerElement extends ElementStyle {
   /** Line color — hex, semantic, or adaptive. */
   color?: ColorValue;
   /** Line thickness in points. */
-  thickness?: number;
+  thickness?: Dimension;
 }
 
 /**
@@ -739,7 +1042,9 @@ export interface DividerElement extends ElementStyle {
 export interface SpacerElement {
   type: "spacer";
   /** Minimum space in points. */
-  minLength?: number;
+  minLength?: Dimension;
+  /** Stable element identity. */
+  id?: string;
 }
 
 /**
@@ -757,7 +1062,7 @@ export interface DateElement extends ElementStyle {
   /** How to format the date. */
   dateStyle?: DateStyle;
   /** Font size in points. */
-  fontSize?: number;
+  fontSize?: Dimension;
   /** Text color — hex, semantic, or adaptive. */
   color?: ColorValue;
 }
@@ -780,22 +1085,123 @@ export interface ChartElement extends ElementStyle {
   type: "chart";
   /** Chart visualization type. */
   chartType: ChartType;
-  /** Array of data points. */
-  chartData: ChartDataPoint[];
-  /** Default color for bars/lines — hex, semantic, or adaptive. */
+  /** Data of a single-series chart. Ignored when `series` is set. */
+  chartData?: ChartDataPoint[];
+  /** Named series; pie charts show the first one. */
+  series?: ChartSeries[];
+  /** Stack series (bar and area charts). */
+  stacked?: boolean;
+  xAxis?: ChartAxis;
+  yAxis?: ChartAxis;
+  /** Horizontal rule lines. */
+  thresholds?: ChartThreshold[];
+  /** Default: `"bottom"` with more than one named series. */
+  legend?: LegendPosition;
+  /** Color of a single-series chart or of the first series — hex, semantic, or adaptive. */
+  tint?: ColorValue;
+}
+
+/** Sparkline drawing style. */
+export type SparklineStyle = "line" | "bar" | "area";
+
+/**
+ * Compact trend line without axes or labels. iOS and Android draw it as an
+ * unlabelled `chart`.
+ *
+ * @example
+ * ```json
+ * { "type": "sparkline", "values": [3, 5, 4, 8, 7], "highlightLast": true, "tint": "#89b4fa" }
+ * ```
+ */
+export interface SparklineElement extends ElementStyle {
+  type: "sparkline";
+  values: number[];
+  /** Default: `"line"`. */
+  sparklineStyle?: SparklineStyle;
+  /** Mark the last value with a dot (line, area) or the highlight color (bar). */
+  highlightLast?: boolean;
+  /** Color of the highlighted value. Defaults to `tint`. */
+  highlightColor?: ColorValue;
   tint?: ColorValue;
 }
 
+/**
+ * A headline number with unit, change indicator and caption. iOS and
+ * Android draw it as a stack of texts.
+ *
+ * @example
+ * ```json
+ * { "type": "stat", "value": "1,284", "unit": "ms", "delta": -4.2, "deltaUnit": "%", "lowerIsBetter": true, "caption": "p95 latency" }
+ * ```
+ */
+export interface StatElement extends ElementStyle {
+  type: "stat";
+  /** The formatted value, e.g. `"1,284"`. */
+  value: TextValue;
+  unit?: TextValue;
+  /** Change since the previous period; green when up, red when down. */
+  delta?: number;
+  /** Suffix of the delta, e.g. `"%"`. */
+  deltaUnit?: string;
+  /** Color decreases green and increases red (latency, cost, …). */
+  lowerIsBetter?: boolean;
+  caption?: TextValue;
+  /** Size of the value. Default: 28. */
+  fontSize?: number;
+  color?: ColorValue;
+}
+
+/** An option of a `segmented` control. */
+export interface SegmentOption {
+  label: TextValue;
+  /** Sent as the action payload when the option is tapped. */
+  value: string;
+  /** SF Symbol shown before the label on iOS and macOS. */
+  systemName?: string;
+}
+
+/**
+ * Row of mutually exclusive options. Tapping one emits `action` with the
+ * option's `value` as payload and moves the selection in the stored config
+ * right away, so the widget reflects the tap before the app responds.
+ * `"picker"` is accepted as an alias type.
+ *
+ * @example
+ * ```json
+ * {
+ *   "type": "segmented",
+ *   "action": "range",
+ *   "selectedIndex": 0,
+ *   "options": [
+ *     { "label": "Day", "value": "day" },
+ *     { "label": "Week", "value": "week" }
+ *   ]
+ * }
+ * ```
+ */
+export interface SegmentedElement extends ElementStyle {
+  type: "segmented" | "picker";
+  options: SegmentOption[];
+  /** Index of the selected option. No option is selected when unset. */
+  selectedIndex?: number;
+  /** Action identifier. Controls are matched by action when the selection is updated, so give each its own. */
+  action?: string;
+  /** Background of the selected option. */
+  tint?: ColorValue;
+  color?: ColorValue;
+  fontSize?: number;
+}
+
 /** Android list item for `list` widgets. */
 export interface ListItem {
   /** Row label text. */
-  text: string;
+  text: TextValue;
   /** Optional checked state marker. */
   checked?: boolean;
   /** Optional action emitted on row tap. */
   action?: string;
   /** Optional payload forwarded with row action. */
-  payload?: string;
+  payload?: ActionPayload;
 }
 
 /**
@@ -807,9 +1213,9 @@ export interface ListElement extends ElementStyle {
   type: "list";
   items: ListItem[];
   /** Space between rows (points). */
-  spacing?: number;
+  spacing?: Dimension;
   /** Row font size (points). */
-  fontSize?: number;
+  fontSize?: Dimension;
   /** Row text color. */
   color?: ColorValue;
 }
@@ -844,8 +1250,8 @@ export interface CanvasArc {
 /** Text draw command. */
 export interface CanvasText {
   draw: "text";
-  x: number; y: number; content: string;
-  fontSize?: number; color?: ColorValue; anchor?: "start" | "middle" | "end";
+  x: number; y: number; content: TextValue;
+  fontSize?: Dimension; color?: ColorValue; anchor?: "start" | "middle" | "end";
 }
 /** SVG path draw command. */
 export interface CanvasPath {
@@ -881,9 +1287,9 @@ export type CanvasDrawCommand = CanvasCircle | CanvasLine | CanvasRect | CanvasA
 export interface CanvasElement extends ElementStyle {
   type: "canvas";
   /** Canvas width in points. */
-  width: number;
+  width: Dimension;
   /** Canvas height in points. */
-  height: number;
+  height: Dimension;
   /** Array of draw commands. */
   elements: CanvasDrawCommand[];
 }
@@ -911,6 +1317,8 @@ export interface LinkElement extends ElementStyle {
   url?: string;
   /** Action identifier — emits `widget-action` event. */
   action?: string;
+  /** Sent with `action` as the event payload. */
+  payload?: ActionPayload;
 }
 
 /**
@@ -930,9 +1338,9 @@ export interface ShapeElement extends ElementStyle {
   /** Stroke color — hex, semantic, or adaptive. */
   stroke?: ColorValue;
   /** Stroke width in points. */
-  strokeWidth?: number;
+  strokeWidth?: Dimension;
   /** Size in points (width & height). */
-  size?: number;
+  size?: Dimension;
 }
 
 /**
@@ -954,7 +1362,7 @@ export interface TimerElement extends ElementStyle {
   /** Count direction. Default: `"down"`. */
   counting?: TimerCounting;
   /** Font size in points. */
-  fontSize?: number;
+  fontSize?: Dimension;
   /** Font weight. */
   fontWeight?: FontWeight;
   /** Text color — hex, semantic, or adaptive. */
@@ -972,19 +1380,19 @@ export interface TimerElement extends ElementStyle {
 export interface LabelElement extends ElementStyle {
   type: "label";
   /** Text string. */
-  text: string;
+  text: TextValue;
   /** SF Symbol name (Apple) or icon hint (Android). */
   systemName: string;
   /** Icon tint color — hex, semantic, or adaptive. */
   iconColor?: ColorValue;
   /** Font size in points. */
-  fontSize?: number;
+  fontSize?: Dimension;
   /** Font weight. */
   fontWeight?: FontWeight;
   /** Text color — hex, semantic, or adaptive. */
   color?: ColorValue;
   /** Space between icon and text (points). */
-  spacing?: number;
+  spacing?: Dimension;
 }
 
 /**
@@ -994,6 +1402,36 @@ export interface LabelElement extends ElementStyle {
  * (`vstack`, `hstack`, `zstack`, `grid`, `link`) contain `children` arrays.
  * Leaf elements render specific content.
  */
+/**
+ * Instance of a component from `WidgetConfig.components`.  Expanded in Rust
+ * before the config is stored; style properties set here (e.g. `id`,
+ * `padding`) override those of the component's root element.
+ *
+ * @example
+ * ```json
+ * { "type": "ref", "component": "header", "params": { "title": "Inbox" } }
+ * ```
+ */
+export interface RefElement extends ElementStyle {
+  type: "ref";
+  /** Name of the component. */
+  component: string;
+  /** Parameter values; missing ones use the component's defaults. */
+  params?: Record<string, unknown>;
+}
+
+/**
+ * A reusable element subtree.  String properties may contain `{{param}}`
+ * placeholders; a value that is exactly `"{{param}}"` takes the parameter
+ * value as-is, so numbers and objects work too.
+ */
+export interface Component {
+  /** Parameter defaults.  A `null` default makes the parameter required. */
+  params?: Record<string, unknown>;
+  /** The element tree. */
+  element: WidgetElement | Record<string, unknown>;
+}
+
 export type WidgetElement =
   | VStackElement
   | HStackElement
@@ -1001,28 +1439,33 @@ export type WidgetElement =
   | GridElement
   | ContainerElement
   | TextElement
+  | RichTextElement
   | ImageElement
   | ProgressElement
   | GaugeElement
   | ButtonElement
   | ToggleElement
+  | SegmentedElement
   | DividerElement
   | SpacerElement
   | DateElement
   | ChartElement
+  | SparklineElement
+  | StatElement
   | ListElement
   | LinkElement
   | ShapeElement
   | TimerElement
   | LabelElement
-  | CanvasElement;
+  | CanvasElement
+  | RefElement;
 
 /**
  * Widget configuration with layouts per size family.
  *
  * Each property defines the root element for that widget size.
  * The native widget picks the layout matching its display size.
- * Unset sizes fall back in order: `small` → `medium` → `large`.
+ * Unset sizes fall back to related families (see `WidgetFamily`).
  *
  * @example
  * ```ts
@@ -1039,7 +1482,10 @@ export type WidgetElement =
  * ```
  */
 export interface WidgetConfig {
-  /** Schema version (default: `1`). Reserved for future changes. */
+  /**
+   * Schema version (default: `1`). Stored configs with an older version are
+   * upgraded by the migrations registered on the Rust plugin builder.
+   */
   version?: number;
   /** Root element for small widgets (e.g. 2x2 on iOS). */
   small?: WidgetElement;
@@ -1047,6 +1493,88 @@ export interface WidgetConfig {
   medium?: WidgetElement;
   /** Root element for large widgets (e.g. 4x4 on iOS). */
   large?: WidgetElement;
+  /** Root element for extra-large widgets (iPad, macOS, StandBy). */
+  extraLarge?: WidgetElement;
+  /** Root element for circular lock screen / watch complications. */
+  accessoryCircular?: WidgetElement;
+  /** Root element for rectangular lock screen / watch complications. */
+  accessoryRectangular?: WidgetElement;
+  /** Single line above the lock screen clock; only text and one image are shown. */
+  accessoryInline?: WidgetElement;
+  /**
+   * Named property sets applied with `"style": "<name>"`.  Properties set on
+   * the element win; a style can extend another via its own `style`.
+   */
+  styles?: Record<string, Record<string, unknown>>;
+  /** Design tokens referenced as `"$<name>"` from element properties. */
+  tokens?: DesignTokens;
+  /** Reusable element subtrees, instantiated with `ref` elements. */
+  components?: Record<string, Component>;
+  /**
+   * Localized strings keyed by locale (`"en"`, `"de-AT"`), then by key.
+   * Referenced from text properties as `{ t: "<key>" }`.
+   */
+  strings?: Record<string, Record<string, LocalizedString>>;
+  /** Locale tried before `"en"` when a string is missing for the current one. */
+  defaultLocale?: string;
+}
+
+/** A named font applied with `font: "$<name>"` or `fontSize: "$<name>"`. */
+export interface FontToken {
+  size?: number;
+  weight?: FontWeight;
+  design?: FontDesign;
+}
+
+/**
+ * Design tokens shared by all layouts.  Resolved in Rust before the config
+ * is stored, so every renderer receives plain values.
+ */
+export interface DesignTokens {
+  /** Referenced by color properties (`color`, `tint`, `background`, …). */
+  colors?: Record<string, ColorValue>;
+  /** Referenced by sizes (`padding`, `spacing`, `cornerRadius`, `frame`, …). */
+  spacing?: Record<string, number>;
+  /** Referenced by `fontSize` (size only) or `font` (size, weight, design). */
+  fonts?: Record<string, FontToken>;
+}
+
+/**
+ * A single JSON Patch (RFC 6902) operation.  `path` is a JSON Pointer into
+ * the config, e.g. `"/small/children/0/content"`.
+ */
+export type PatchOp =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string }
+  | { op: "replace"; path: string; value: unknown }
+  | { op: "move"; from: string; path: string }
+  | { op: "copy"; from: string; path: string }
+  | { op: "test"; path: string; value: unknown };
+
+/**
+ * Merge `props` into the element whose `id` matches.  A `null` prop removes
+ * that property from the element.
+ */
+export interface ElementPatch {
+  /** Target element `id`. */
+  id: string;
+  /** Properties to set (or remove with `null`). */
+  props: Record<string, unknown>;
+}
+
+/** One update accepted by `patchWidgetConfig`. */
+export type ConfigPatch = PatchOp | ElementPatch;
+
+/**
+ * Payload of the `widget-config-patch` event, emitted on desktop when a
+ * group's config changes.  Contains only the operations needed to turn the
+ * previously pushed config into the new one.
+ */
+export interface WidgetConfigPatchEvent {
+  /** Group whose config changed. */
+  group: string;
+  /** Operations to apply, in order. */
+  ops: PatchOp[];
 }
 
 /**
@@ -1094,24 +1622,114 @@ export async function setWidgetConfig(
 /**
  * Read the current widget UI configuration from the data store.
  *
- * @param group - Widget group identifier.
+ * @param group  - Widget group identifier.
+ * @param locale - Locale to resolve localized strings for.  Defaults to the
+ *                 locale set on the Rust `Builder`, or the system locale.
  * @returns The current `WidgetConfig`, or `null` if none has been set.
  */
 export async function getWidgetConfig(
   group: string,
+  locale?: string,
 ): Promise<WidgetConfig | null> {
   if (!group) throw new Error("getWidgetConfig: 'group' must not be empty");
-  return await invoke<WidgetConfig | null>(`${PLUGIN_ID}|get_widget_config`, { group });
+  return await invoke<WidgetConfig | null>(`${PLUGIN_ID}|get_widget_config`, { group, locale });
+}
+
+/**
+ * Update part of the stored widget config without resending all of it.
+ *
+ * Operations are applied in Rust to the stored config, the result is
+ * validated and persisted, and desktop widget windows receive the change
+ * as a `widget-config-patch` event.  Either all operations apply or none.
+ *
+ * @param group      - Widget group identifier.
+ * @param ops        - JSON Patch operations and/or `{ id, props }` updates.
+ * @param skipReload - Skip native widget reload. Default: `false`.
+ * @returns The patched config.
+ *
+ * @example
+ * ```ts
+ * await patchWidgetConfig("group.com.example.myapp", [
+ *   { id: "temp", props: { content: "74°" } },
+ *   { op: "replace", path: "/small/background", value: "#0f172a" },
+ * ]);
+ * ```
+ */
+export async function patchWidgetConfig(
+  group: string,
+  ops: ConfigPatch[],
+  skipReload = false,
+): Promise<WidgetConfig> {
+  if (!group) throw new Error("patchWidgetConfig: 'group' must not be empty");
+  return await invoke<WidgetConfig>(`${PLUGIN_ID}|patch_widget_config`, {
+    group, ops, skipReload,
+  });
+}
+
+/**
+ * Get the JSON Schema (draft-07) for `WidgetConfig`, generated from the Rust
+ * models.  Useful for validating hand-written configs before sending them.
+ * The same schema ships as `schemas/widget-config.schema.json` in this
+ * package.
+ */
+export async function getWidgetConfigSchema(): Promise<Record<string, unknown>> {
+  return await invoke<Record<string, unknown>>(`${PLUGIN_ID}|get_widget_config_schema`);
+}
+
+/** An accessibility problem reported by `lintWidgetConfig`. */
+export interface LintIssue {
+  /** `"image-label"`, `"icon-button-label"` or `"hidden-interactive"`. */
+  rule: string;
+  /** JSON Pointer of the element in the resolved config, e.g. `/small/children/0`. */
+  path: string;
+  message: string;
+}
+
+/**
+ * Check a config for elements screen readers cannot announce: images and
+ * canvases without `accessibilityLabel`, buttons and links that only show
+ * an icon, and tappable elements hidden from screen readers.
+ *
+ * Styles, components and localized strings are resolved first; invalid
+ * configs are rejected with the same error as `setWidgetConfig`.
+ */
+export async function lintWidgetConfig(config: WidgetConfig): Promise<LintIssue[]> {
+  return await invoke<LintIssue[]>(`${PLUGIN_ID}|lint_widget_config`, { config });
 }
 
 // ─── Widget Action API ──────────────────────────────────────────────────────
 
-/** Payload delivered by the `widget-action` event. */
+/**
+ * Payload delivered by the `widget-action` event. Fields other than
+ * `action` and `payload` are set where the platform knows them.
+ */
 export interface WidgetActionPayload {
+  /**
+   * Queue id, set on queued actions: pass it to `ackWidgetActions` once the
+   * action is handled.
+   */
+  id?: string;
+  /**
+   * Unique per tap. An action arriving again with the same nonce is a retry
+   * of the same tap.
+   */
+  nonce?: string;
   /** The action identifier from the button config. */
   action: string;
-  /** Optional extra data (e.g. serialized JSON from the widget). */
-  payload?: string;
+  /** The element's payload, if any. */
+  payload?: ActionPayload;
+  /** Group of the widget that was tapped. */
+  group?: string;
+  /** Layout family the widget showed, e.g. `"small"`. */
+  kind?: string;
+  /** Label of the desktop window the action came from. */
+  window?: string;
+  /** `id` of the tapped element. */
+  elementId?: string;
+  /** Time of the tap in milliseconds since the Unix epoch. */
+  timestamp?: number;
+  /** Failed runs of the action's Rust handler, on actions queued for a retry. */
+  attempts?: number;
 }
 
 /**
@@ -1121,19 +1739,36 @@ export interface WidgetActionPayload {
  * window that called `onWidgetAction()` (or `listen("widget-action", …)`)
  * will receive it — including the main application window.
  *
+ * When `group` is given, segmented controls of that group that emit
+ * `action` select the option whose value is `payload`, and a toggle bound
+ * to a store key flips (or takes the state `"true"`/`"false"` given as
+ * `payload`) before the event is sent, as a tap on the control would. The
+ * event then carries the toggle's new state as payload.
+ *
+ * Actions with a handler registered in Rust (`widget().on_action`) run
+ * that handler instead of emitting the event; a handler error rejects the
+ * returned promise.
+ *
+ * Resolves to `false` when the action was dropped as a duplicate of one
+ * sent moments before, and rejects with a `throttled` error when a rate
+ * limit set on the plugin builder drops it.
+ *
  * @param action  - Action identifier (matches `ButtonElement.action`).
- * @param payload - Optional extra string payload.
+ * @param payload - Optional payload, any JSON value.
+ * @param group   - Widget group whose segmented controls follow the action.
  */
 export async function widgetAction(
   action: string,
-  payload?: string,
+  payload?: ActionPayload,
+  group?: string,
 ): Promise<boolean> {
   if (!action) throw new Error("widgetAction: 'action' must not be empty");
-  return await invoke<boolean>(`${PLUGIN_ID}|widget_action`, { action, payload });
+  return await invoke<boolean>(`${PLUGIN_ID}|widget_action`, { action, payload, group });
 }
 
 /**
- * Listen for `widget-action` events emitted by widget buttons.
+ * Listen for `widget-action` events emitted by widget buttons. Actions
+ * handled in Rust with `widget().on_action` are not emitted.
  *
  * @param callback - Called with the action payload each time a widget button
  *                   with an `action` field is tapped.
@@ -1159,7 +1794,64 @@ export async function onWidgetAction(
   });
 }
 
-/** Poll pending widget actions queued by native side (Android fallback path). */
+/**
+ * Payload of the `widget-deeplink` event, sent when a desktop widget opens
+ * a URL with one of the app's schemes (`Builder::deep_link_scheme`).
+ */
+export interface WidgetDeepLink {
+  url: string;
+  /** Host and path of the URL without slashes around, e.g. `"settings/theme"`. */
+  route: string;
+  /** Decoded query parameters. */
+  params: Record<string, string>;
+  /** Group of the widget that was tapped. */
+  group?: string;
+  /** Label of the desktop window the link came from. */
+  window?: string;
+  /** `id` of the tapped element. */
+  elementId?: string;
+}
+
+/**
+ * Listen for deep links opened by desktop widgets. On mobile, the system
+ * opens the app at the URL instead; handle those with the deep-link plugin.
+ *
+ * @example
+ * ```ts
+ * await onWidgetDeepLink(({ route, params }) => {
+ *   if (route === "settings/theme") openThemeSettings(params.mode);
+ * });
+ * ```
+ */
+export async function onWidgetDeepLink(
+  callback: (link: WidgetDeepLink) => void,
+): Promise<UnlistenFn> {
+  return await listen<WidgetDeepLink>("widget-deeplink", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Open `url` as a tap on a desktop widget of `group` would: URLs with the
+ * app's scheme are sent as the `widget-deeplink` event, and web pages
+ * allow-listed with `Builder::allow_url` open in the browser. Other URLs
+ * are refused. Desktop only.
+ */
+export async function openWidgetUrl(url: string, group?: string): Promise<boolean> {
+  return await invoke<boolean>(`${PLUGIN_ID}|open_widget_url`, { url, group });
+}
+
+/**
+ * Poll pending widget actions: those the Android widget queued while the
+ * app was not running, and those not acknowledged yet.  Actions with a
+ * Rust handler run it and are left out of the result.
+ *
+ * Actions emitted as `widget-action` stay queued until passed to
+ * `ackWidgetActions` or returned here, so ones fired before a listener was
+ * registered are not lost.  With `Builder::manual_action_ack`, the returned
+ * actions stay queued too, and are returned again by the next poll, until
+ * acknowledged.  Otherwise they are acknowledged already.
+ */
 export async function pollPendingWidgetActions(
   group: string,
 ): Promise<WidgetActionPayload[]> {
@@ -1167,7 +1859,7 @@ export async function pollPendingWidgetActions(
   try {
     res = await invoke<unknown>(`${PLUGIN_ID}|poll_pending_actions`, { group });
   } catch (e) {
-    const msg = String(e ?? "");
+    const msg = isWidgetError(e) ? e.message : String(e ?? "");
     // iOS/macOS builds may not expose this Android-specific command path.
     if (msg.includes("No command pollPendingActions") || msg.includes("pollPendingActions")) {
       return [];
@@ -1180,16 +1872,100 @@ export async function pollPendingWidgetActions(
   if (!Array.isArray(raw)) return [];
   const parsed: Array<WidgetActionPayload | null> = raw.map((item) => {
     if (!item || typeof item !== "object") return null;
-    const obj = item as { action?: unknown; payload?: unknown };
+    const obj = item as Partial<Record<keyof WidgetActionPayload, unknown>>;
     if (typeof obj.action !== "string" || obj.action.length === 0) return null;
-    return {
-      action: obj.action,
-      ...(typeof obj.payload === "string" ? { payload: obj.payload } : {}),
-    };
+    const { payload, ...meta } = obj as WidgetActionPayload;
+    return { ...meta, ...(payload != null ? { payload } : {}) };
   });
   return parsed.filter((v): v is WidgetActionPayload => v !== null);
 }
 
+/**
+ * Acknowledge actions by `id`, removing them from the queue of `group`.
+ * Returns how many were removed.
+ */
+export async function ackWidgetActions(group: string, ids: string[]): Promise<number> {
+  if (ids.length === 0) return 0;
+  return await invoke<number>(`${PLUGIN_ID}|ack_actions`, { group, ids });
+}
+
+/** Counts of the actions the plugin accepted and dropped since the app started. */
+export interface WidgetActionStats {
+  accepted: number;
+  /** Retried and duplicate taps dropped. */
+  duplicates: number;
+  /** Actions dropped by rate limits. */
+  rateLimited: number;
+  /** Dropped actions by action name. */
+  dropped: Record<string, number>;
+}
+
+/**
+ * Get the counts of accepted and dropped actions. Useful to tune the
+ * builder's `action_dedup_window` and `action_rate_limit`.
+ */
+export async function getWidgetActionStats(): Promise<WidgetActionStats> {
+  return await invoke<WidgetActionStats>(`${PLUGIN_ID}|get_action_stats`);
+}
+
+// ─── Activity Log ───────────────────────────────────────────────────────────
+
+/** An action or timeline reload recorded by the plugin. */
+export interface WidgetActivityEntry {
+  /** Milliseconds since the Unix epoch. */
+  timestamp: number;
+  kind: "action" | "reload";
+  group?: string;
+  window?: string;
+  action?: string;
+  payload?: ActionPayload;
+  elementId?: string;
+  /** Widget kind of a reload, or `"all"`. */
+  target?: string;
+  /**
+   * `handled` by a Rust handler, `emitted` as the `widget-action` event,
+   * dropped as a `duplicate` or by a rate limit (`rateLimited`),
+   * `reloaded`, or `failed` with `error`.
+   */
+  outcome: "handled" | "emitted" | "duplicate" | "rateLimited" | "reloaded" | "failed";
+  error?: string;
+}
+
+/** Which activity entries to return. */
+export interface WidgetActivityQuery {
+  /** Only entries of this group. */
+  group?: string;
+  /** Only entries at or after this time, in milliseconds since the epoch. */
+  since?: number;
+  /** At most this many of the newest entries. */
+  limit?: number;
+}
+
+/**
+ * Get the plugin's log of actions and reloads, oldest first. The log
+ * keeps the newest 200 entries unless configured otherwise with
+ * `Builder::activity_log`.
+ *
+ * @example
+ * ```ts
+ * const recent = await getWidgetActivity({ group, limit: 20 });
+ * const failed = recent.filter((e) => e.outcome === "failed");
+ * ```
+ */
+export async function getWidgetActivity(
+  query?: WidgetActivityQuery,
+): Promise<WidgetActivityEntry[]> {
+  return await invoke<WidgetActivityEntry[]>(`${PLUGIN_ID}|get_widget_activity`, { query });
+}
+
+/**
+ * Export the log of actions and reloads as JSON lines, one entry per line,
+ * e.g. to attach it to a bug report.
+ */
+export async function exportWidgetActivity(query?: WidgetActivityQuery): Promise<string> {
+  return await invoke<string>(`${PLUGIN_ID}|export_widget_activity`, { query });
+}
+
 // ─── Widget Updater ─────────────────────────────────────────────────────────
 
 /**
@@ -1263,8 +2039,10 @@ export async function startWidgetUpdater(
      *  Backend throttles to once per 15 min on iOS/macOS. */
     reload?: boolean;
     /** Subscribe to `widget-action` events for the lifetime of this updater.
+     *  Actions queued before the updater started are delivered first, and
+     *  every delivered action is acknowledged.
      *  The listener is automatically removed when the returned stop function is called. */
-    onAction?: (action: string, payload?: string) => void;
+    onAction?: (action: string, payload?: ActionPayload) => void;
   },
 ): Promise<() => void> {
   const intervalMs = options?.intervalMs ?? 1000;
@@ -1298,9 +2076,24 @@ export async function startWidgetUpdater(
   let actionUnsub: (() => void) | null = null;
   if (options?.onAction) {
     const handler = options.onAction;
-    actionUnsub = await onWidgetAction((data) => {
+    const seen = new Set<string>();
+    const deliver = (data: WidgetActionPayload) => {
+      // Android emits taps live and also queues them; both carry the
+      // tap's nonce, while only the queued one has an id.
+      const key = data.nonce ?? data.id;
+      if (key) {
+        if (seen.has(key)) return;
+        seen.add(key);
+      }
       handler(data.action, data.payload);
-    });
+      if (data.id) {
+        ackWidgetActions(data.group ?? group, [data.id]).catch(console.error);
+      }
+    };
+    actionUnsub = await onWidgetAction(deliver);
+    // Listen before polling so nothing fired in between is missed; actions
+    // arriving both ways are delivered once.
+    (await pollPendingWidgetActions(group)).forEach(deliver);
   }
 
   return () => {
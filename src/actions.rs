@@ -0,0 +1,318 @@
+//! State changes caused by widget interactions.
+//!
+//! Interactive elements keep their state in the widget config.  When one
+//! is tapped, the plugin updates the stored config right away instead of
+//! waiting for the app to push a new one, so the widget reflects the tap
+//! immediately.  The app still receives the `widget-action` event and may
+//! overwrite the state with its own config.
+//!
+//! Taps queued by native widgets while the app was not listening are read
+//! back with [`pending_event`].
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value};
+
+use crate::models::{ConfigPatch, PatchOp, WidgetActionEvent};
+use crate::patch::escape_token;
+
+/// Kind of an interactive control that a tap changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlKind {
+    /// A `segmented` or `picker` element.
+    Selector,
+    /// A `toggle` bound to a store key.
+    Toggle,
+}
+
+/// The interactive controls of a config, kept per group so that taps on
+/// other elements neither read nor patch the stored config.
+#[derive(Debug, Clone, Default)]
+pub struct Controls(Vec<Control>);
+
+#[derive(Debug, Clone)]
+struct Control {
+    kind: ControlKind,
+    action: String,
+    id: Option<String>,
+}
+
+impl Controls {
+    /// The controls of `doc`, the config as the app set it.
+    pub fn of(doc: &Value) -> Self {
+        let mut controls = Vec::new();
+        visit(doc, "", &mut |_, element| {
+            let kind = match element.get("type").and_then(Value::as_str) {
+                Some("segmented" | "picker") => ControlKind::Selector,
+                Some("toggle") if element.get("key").and_then(Value::as_str).is_some() => {
+                    ControlKind::Toggle
+                }
+                _ => return,
+            };
+            let Some(action) = element.get("action").and_then(Value::as_str) else {
+                return;
+            };
+            controls.push(Control {
+                kind,
+                action: action.to_string(),
+                id: element
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            });
+        });
+        Self(controls)
+    }
+
+    /// The kind of control a tap emitting `action` changes.  A tap that
+    /// names its element only matches the control with that `id`; one
+    /// without, e.g. from the app or a native widget, matches the first
+    /// control emitting `action`.
+    pub fn find(&self, action: &str, element_id: Option<&str>) -> Option<ControlKind> {
+        self.0
+            .iter()
+            .find(|c| {
+                c.action == action && element_id.map_or(true, |id| c.id.as_deref() == Some(id))
+            })
+            .map(|c| c.kind)
+    }
+}
+
+/// Patches that select the option with `value` in every `segmented`
+/// element of `doc` whose action is `action`.
+///
+/// `doc` is the config as the app set it.  Controls that already show the
+/// option, or have no option with that value, are left alone, so an empty
+/// list means there is nothing to store.
+pub fn select_option(doc: &Value, action: &str, value: &str) -> Vec<ConfigPatch> {
+    let mut patches = Vec::new();
+    visit(doc, "", &mut |path, element| {
+        if !matches!(
+            element.get("type").and_then(Value::as_str),
+            Some("segmented" | "picker")
+        ) || element.get("action").and_then(Value::as_str) != Some(action)
+        {
+            return;
+        }
+        let index = element
+            .get("options")
+            .and_then(Value::as_array)
+            .and_then(|options| {
+                options
+                    .iter()
+                    .position(|o| o.get("value").and_then(Value::as_str) == Some(value))
+            });
+        let Some(index) = index else {
+            return;
+        };
+        if element.get("selectedIndex").and_then(Value::as_u64) != Some(index as u64) {
+            patches.push(ConfigPatch::Op(PatchOp::Add {
+                path: format!("{path}/selectedIndex"),
+                value: index.into(),
+            }));
+        }
+    });
+    patches
+}
+
+/// A toggle state change: the store key to write and the config patches
+/// that show the new state.
+#[derive(Debug, Clone)]
+pub struct ToggleChange {
+    pub key: String,
+    pub is_on: bool,
+    pub patches: Vec<ConfigPatch>,
+}
+
+/// The change for a tap on the toggle of `doc` that emits `action` and is
+/// bound to a store key, or `None` when there is no such toggle.
+///
+/// `is_on` is the state to set; `None` flips the current state, read with
+/// `current` from the store or taken from the toggle's `isOn`.  Every
+/// toggle bound to the same key is updated.
+pub fn set_toggle(
+    doc: &Value,
+    action: &str,
+    is_on: Option<bool>,
+    current: impl Fn(&str) -> Option<bool>,
+) -> Option<ToggleChange> {
+    let mut target = None;
+    visit(doc, "", &mut |_, element| {
+        if target.is_none()
+            && element.get("type").and_then(Value::as_str) == Some("toggle")
+            && element.get("action").and_then(Value::as_str) == Some(action)
+        {
+            if let Some(key) = element.get("key").and_then(Value::as_str) {
+                let shown = element.get("isOn").and_then(Value::as_bool);
+                target = Some((key.to_string(), shown));
+            }
+        }
+    });
+    let (key, shown) = target?;
+    let is_on = is_on.unwrap_or_else(|| !current(&key).or(shown).unwrap_or(false));
+    let patches = sync_toggles(doc, |k| (k == key).then_some(is_on));
+    Some(ToggleChange {
+        key,
+        is_on,
+        patches,
+    })
+}
+
+/// Parse a stored toggle state.
+pub fn parse_state(value: &str) -> Option<bool> {
+    match value.trim() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// The toggle state asked for by an action payload: `true`/`false`, or a
+/// string holding a stored state.
+pub fn payload_state(payload: &Value) -> Option<bool> {
+    match payload {
+        Value::Bool(is_on) => Some(*is_on),
+        Value::String(s) => parse_state(s),
+        _ => None,
+    }
+}
+
+/// Patches that set `isOn` of every bound toggle in `doc` to the state of
+/// its key, so a config pushed by the app shows the state the plugin owns.
+/// Keys without a state keep their `isOn`.
+pub fn sync_toggles(doc: &Value, state: impl Fn(&str) -> Option<bool>) -> Vec<ConfigPatch> {
+    let mut patches = Vec::new();
+    visit(doc, "", &mut |path, element| {
+        if element.get("type").and_then(Value::as_str) != Some("toggle") {
+            return;
+        }
+        let Some(is_on) = element.get("key").and_then(Value::as_str).and_then(&state) else {
+            return;
+        };
+        if element.get("isOn").and_then(Value::as_bool) != Some(is_on) {
+            patches.push(ConfigPatch::Op(PatchOp::Add {
+                path: format!("{path}/isOn"),
+                value: is_on.into(),
+            }));
+        }
+    });
+    patches
+}
+
+/// Read an entry of the `__widget_pending_actions__` queue.  Entries are
+/// action names, or objects with the fields of [`WidgetActionEvent`]
+/// written by native widgets.  Entries without a timestamp get the
+/// current time.
+pub fn pending_event(entry: &Value) -> Option<WidgetActionEvent> {
+    let mut event = match entry {
+        Value::String(action) => WidgetActionEvent::new(action.as_str(), None),
+        Value::Object(_) => serde_json::from_value(entry.clone()).ok()?,
+        _ => return None,
+    };
+    if event.action.is_empty() {
+        return None;
+    }
+    if event.timestamp == 0 {
+        event.timestamp = now_millis();
+    }
+    Some(event)
+}
+
+/// Milliseconds since the Unix epoch, the unit of event timestamps.
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Keys whose contents are app data or unexpanded component definitions,
+/// never live controls.
+const SKIPPED_KEYS: &[&str] = &["payload", "params", "components"];
+
+/// Call `f` with the JSON Pointer and contents of every object in `value`.
+fn visit(value: &Value, path: &str, f: &mut impl FnMut(&str, &Map<String, Value>)) {
+    match value {
+        Value::Object(map) => {
+            f(path, map);
+            for (key, child) in map {
+                if SKIPPED_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                visit(child, &format!("{path}/{}", escape_token(key)), f);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                visit(child, &format!("{path}/{i}"), f);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn controls() -> Controls {
+        Controls::of(&json!({
+            "version": 1,
+            "small": {
+                "type": "vstack",
+                "children": [
+                    { "type": "segmented", "id": "range", "action": "pick", "options": [] },
+                    { "type": "toggle", "id": "alarm", "action": "flip", "key": "alarm" },
+                    { "type": "toggle", "id": "loose", "action": "loose" },
+                    { "type": "button", "id": "go", "action": "pick" }
+                ]
+            }
+        }))
+    }
+
+    #[test]
+    fn taps_find_their_control_by_element_id() {
+        let controls = controls();
+        assert_eq!(
+            controls.find("pick", Some("range")),
+            Some(ControlKind::Selector)
+        );
+        assert_eq!(controls.find("pick", Some("go")), None);
+        assert_eq!(
+            controls.find("flip", Some("alarm")),
+            Some(ControlKind::Toggle)
+        );
+    }
+
+    #[test]
+    fn taps_without_element_id_match_by_action() {
+        let controls = controls();
+        assert_eq!(controls.find("pick", None), Some(ControlKind::Selector));
+        assert_eq!(controls.find("other", None), None);
+    }
+
+    #[test]
+    fn unbound_toggles_are_not_controls() {
+        assert_eq!(controls().find("loose", None), None);
+    }
+
+    #[test]
+    fn payloads_and_component_definitions_hold_no_controls() {
+        let doc = json!({
+            "version": 1,
+            "components": {
+                "row": { "type": "toggle", "action": "flip", "key": "alarm" }
+            },
+            "small": {
+                "type": "button",
+                "action": "save",
+                "payload": { "type": "segmented", "action": "pick", "options": [] }
+            }
+        });
+        let controls = Controls::of(&doc);
+        assert_eq!(controls.find("flip", None), None);
+        assert_eq!(controls.find("pick", None), None);
+        assert!(set_toggle(&doc, "flip", Some(true), |_| None).is_none());
+        assert!(sync_toggles(&doc, |_| Some(true)).is_empty());
+    }
+}
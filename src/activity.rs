@@ -0,0 +1,356 @@
+//! Log of widget actions and reloads.
+//!
+//! When a widget button "did nothing", the log shows whether the action
+//! reached the plugin and what became of it: handled in Rust, emitted to
+//! the webview, dropped as a duplicate or by a rate limit, or failed.
+//! Reloads of widget timelines are logged too.  The log keeps the newest
+//! entries up to a capacity; when persisted, it is appended to a JSON lines
+//! file that survives restarts and is compacted as it grows.
+
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::actions::now_millis;
+use crate::models::WidgetActionEvent;
+
+/// Settings of the log, collected by the plugin builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivitySettings {
+    /// Entries kept; zero turns the log off.
+    pub capacity: usize,
+    /// Whether the log is kept in a file across restarts.
+    pub persist: bool,
+}
+
+impl Default for ActivitySettings {
+    /// 200 entries, in memory only.
+    fn default() -> Self {
+        Self {
+            capacity: 200,
+            persist: false,
+        }
+    }
+}
+
+/// What an entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    Action,
+    Reload,
+}
+
+/// What became of an action or reload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    /// A Rust handler ran without error.
+    Handled,
+    /// Sent to the webview as the `widget-action` event.
+    Emitted,
+    /// Dropped as a duplicate or retry.
+    Duplicate,
+    /// Dropped by a rate limit.
+    RateLimited,
+    /// Timelines were reloaded.
+    Reloaded,
+    /// See the entry's `error`.
+    Failed,
+}
+
+/// An entry of the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub kind: ActivityKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element_id: Option<String>,
+    /// Widget kind of a reload, or `all`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    pub outcome: Outcome,
+    /// Error of a failed action or reload, including handler errors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ActivityEntry {
+    fn action(event: &WidgetActionEvent, outcome: Outcome, error: Option<String>) -> Self {
+        Self {
+            timestamp: now_millis(),
+            kind: ActivityKind::Action,
+            group: event.group.clone(),
+            window: event.window.clone(),
+            action: Some(event.action.clone()),
+            payload: event.payload.clone(),
+            element_id: event.element_id.clone(),
+            target: None,
+            outcome,
+            error,
+        }
+    }
+}
+
+/// Entries to return from the log.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityQuery {
+    /// Only entries of this group.
+    pub group: Option<String>,
+    /// Only entries at or after this time, in milliseconds since the epoch.
+    pub since: Option<u64>,
+    /// At most this many of the newest entries.
+    pub limit: Option<usize>,
+}
+
+pub struct ActivityLog {
+    capacity: usize,
+    file: Option<PathBuf>,
+    state: Mutex<State>,
+}
+
+struct State {
+    entries: VecDeque<ActivityEntry>,
+    /// Lines in the file, which is rewritten once it holds twice the
+    /// capacity.
+    file_lines: usize,
+}
+
+impl ActivityLog {
+    /// A log with `settings`, stored in `file` when persisted.  Entries in
+    /// an existing file are loaded.
+    pub fn new(settings: ActivitySettings, file: Option<PathBuf>) -> Self {
+        let file = file.filter(|_| settings.persist && settings.capacity > 0);
+        let mut entries = VecDeque::new();
+        let mut file_lines = 0;
+        if let Some(text) = file.as_ref().and_then(|f| fs::read_to_string(f).ok()) {
+            for line in text.lines() {
+                file_lines += 1;
+                // Lines cut short by a crash are skipped.
+                if let Ok(entry) = serde_json::from_str(line) {
+                    entries.push_back(entry);
+                }
+            }
+            let excess = entries.len().saturating_sub(settings.capacity);
+            entries.drain(..excess);
+        }
+        Self {
+            capacity: settings.capacity,
+            file,
+            state: Mutex::new(State {
+                entries,
+                file_lines,
+            }),
+        }
+    }
+
+    /// Log what became of `event`.
+    pub fn action(&self, event: &WidgetActionEvent, outcome: Outcome) {
+        self.record(ActivityEntry::action(event, outcome, None));
+    }
+
+    /// Log that `event` failed with `error`.
+    pub fn action_failed(&self, event: &WidgetActionEvent, error: &crate::Error) {
+        self.record(ActivityEntry::action(
+            event,
+            Outcome::Failed,
+            Some(error.to_string()),
+        ));
+    }
+
+    /// Log a reload of `target`, a widget kind or `all`, and pass its
+    /// result through.
+    pub fn reload<T>(&self, target: &str, result: crate::Result<T>) -> crate::Result<T> {
+        let (outcome, error) = match &result {
+            Ok(_) => (Outcome::Reloaded, None),
+            Err(e) => (Outcome::Failed, Some(e.to_string())),
+        };
+        self.record(ActivityEntry {
+            timestamp: now_millis(),
+            kind: ActivityKind::Reload,
+            group: None,
+            window: None,
+            action: None,
+            payload: None,
+            element_id: None,
+            target: Some(target.to_string()),
+            outcome,
+            error,
+        });
+        result
+    }
+
+    fn record(&self, entry: ActivityEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.entries.push_back(entry);
+        if state.entries.len() > self.capacity {
+            state.entries.pop_front();
+        }
+        if let Some(file) = &self.file {
+            if let Err(e) = persist(file, &mut state, self.capacity) {
+                log::warn!("widget activity log {}: {e}", file.display());
+            }
+        }
+    }
+
+    /// Entries matching `query`, oldest first.
+    pub fn entries(&self, query: &ActivityQuery) -> Vec<ActivityEntry> {
+        let state = self.state.lock().unwrap();
+        let mut entries: Vec<ActivityEntry> = state
+            .entries
+            .iter()
+            .filter(|e| query.group.is_none() || e.group == query.group)
+            .filter(|e| query.since.map_or(true, |since| e.timestamp >= since))
+            .cloned()
+            .collect();
+        if let Some(limit) = query.limit {
+            entries.drain(..entries.len().saturating_sub(limit));
+        }
+        entries
+    }
+
+    /// Entries matching `query` as JSON lines, oldest first.
+    pub fn export(&self, query: &ActivityQuery) -> String {
+        self.entries(query)
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+}
+
+/// Append the newest entry to `file`, or rewrite it with the entries in
+/// memory once it has grown to twice the capacity.
+fn persist(file: &PathBuf, state: &mut State, capacity: usize) -> std::io::Result<()> {
+    if state.file_lines >= capacity * 2 {
+        let text: String = state
+            .entries
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect();
+        // Not `activity.tmp`, the temporary file of a group named
+        // `activity`.
+        let tmp = file.with_extension("jsonl.tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, file)?;
+        state.file_lines = state.entries.len();
+        return Ok(());
+    }
+    let Some(entry) = state.entries.back() else {
+        return Ok(());
+    };
+    let line = serde_json::to_string(entry)? + "\n";
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)?
+        .write_all(line.as_bytes())?;
+    state.file_lines += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tap(action: &str) -> WidgetActionEvent {
+        let mut event = WidgetActionEvent::new(action, None);
+        event.group = Some("g".into());
+        event
+    }
+
+    fn actions(log: &ActivityLog) -> Vec<String> {
+        log.entries(&ActivityQuery::default())
+            .into_iter()
+            .filter_map(|e| e.action)
+            .collect()
+    }
+
+    /// A fresh log file in the temporary directory.
+    fn log_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("widgets-activity-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("activity.jsonl")
+    }
+
+    #[test]
+    fn only_the_newest_entries_are_kept() {
+        let settings = ActivitySettings {
+            capacity: 3,
+            persist: false,
+        };
+        let log = ActivityLog::new(settings, None);
+        for action in ["a", "b", "c", "d", "e"] {
+            log.action(&tap(action), Outcome::Emitted);
+        }
+        assert_eq!(actions(&log), ["c", "d", "e"]);
+        let query = ActivityQuery {
+            limit: Some(1),
+            ..ActivityQuery::default()
+        };
+        assert_eq!(log.entries(&query)[0].action.as_deref(), Some("e"));
+    }
+
+    #[test]
+    fn persisted_entries_are_reloaded() {
+        let file = log_file("reload");
+        let settings = ActivitySettings {
+            capacity: 3,
+            persist: true,
+        };
+        let log = ActivityLog::new(settings, Some(file.clone()));
+        log.action(&tap("a"), Outcome::Handled);
+        log.action_failed(&tap("b"), &crate::Error::new("boom"));
+        let reloaded = ActivityLog::new(settings, Some(file.clone()));
+        assert_eq!(actions(&reloaded), ["a", "b"]);
+        let failed = &reloaded.entries(&ActivityQuery::default())[1];
+        assert_eq!(failed.outcome, Outcome::Failed);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+    }
+
+    #[test]
+    fn the_file_is_compacted_to_the_capacity() {
+        let file = log_file("compact");
+        let settings = ActivitySettings {
+            capacity: 3,
+            persist: true,
+        };
+        let log = ActivityLog::new(settings, Some(file.clone()));
+        for action in ["a", "b", "c", "d", "e", "f", "g"] {
+            log.action(&tap(action), Outcome::Emitted);
+        }
+        let lines = fs::read_to_string(&file).unwrap().lines().count();
+        assert!(lines <= 6, "{lines} lines");
+        assert!(!file.with_extension("jsonl.tmp").exists());
+        assert_eq!(
+            actions(&ActivityLog::new(settings, Some(file.clone()))),
+            ["e", "f", "g"]
+        );
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+    }
+}
@@ -0,0 +1,130 @@
+//! Downsampling of chart data.
+//!
+//! Widget payloads are small (WidgetKit timelines and `RemoteViews` are
+//! capped at a few hundred KB), so a line chart of a day of minute samples
+//! should be thinned before it is stored.  [`downsample`] uses
+//! Largest-Triangle-Three-Buckets, which keeps the visual shape, peaks and
+//! dips of a series while dropping points that lie on a straight run.
+
+use crate::models::{ChartDataPoint, ChartType, WidgetConfig, WidgetElement};
+
+/// Reduce `points` to at most `max_points`, keeping the first and last
+/// point.  Series with `max_points` or fewer points are returned unchanged.
+pub fn downsample(points: &[ChartDataPoint], max_points: usize) -> Vec<ChartDataPoint> {
+    select(&[points], max_points)
+        .into_iter()
+        .map(|i| points[i].clone())
+        .collect()
+}
+
+/// Downsample every line and area chart in every layout of `config` to at
+/// most `max_points` points per series.
+///
+/// Series of one chart keep the same indices, so points stay aligned on the
+/// shared x axis.  Bar and pie charts are left alone, since every bar is a
+/// labelled category.
+pub fn limit_chart_points(config: &mut WidgetConfig, max_points: usize) {
+    for root in [
+        &mut config.small,
+        &mut config.medium,
+        &mut config.large,
+        &mut config.extra_large,
+        &mut config.accessory_circular,
+        &mut config.accessory_rectangular,
+        &mut config.accessory_inline,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        limit_element(root, max_points);
+    }
+}
+
+fn limit_element(element: &mut WidgetElement, max_points: usize) {
+    if let WidgetElement::Chart {
+        chart_type: ChartType::Line | ChartType::Area,
+        chart_data,
+        series,
+        ..
+    } = element
+    {
+        let all: Vec<&[ChartDataPoint]> = if series.is_empty() {
+            vec![chart_data.as_slice()]
+        } else {
+            series.iter().map(|s| s.data.as_slice()).collect()
+        };
+        let keep = select(&all, max_points);
+        let thin = |data: &mut Vec<ChartDataPoint>| {
+            *data = keep.iter().filter_map(|&i| data.get(i).cloned()).collect();
+        };
+        if series.is_empty() {
+            thin(chart_data);
+        } else {
+            series.iter_mut().for_each(|s| thin(&mut s.data));
+        }
+    }
+    for child in element.children_mut() {
+        limit_element(child, max_points);
+    }
+}
+
+/// Indices to keep, chosen by LTTB over the x position (index) and the
+/// values of all `series`.  A candidate's score is the sum of its triangle
+/// areas across series, so a spike in any series is kept.
+fn select(series: &[&[ChartDataPoint]], max_points: usize) -> Vec<usize> {
+    let len = series.iter().map(|s| s.len()).max().unwrap_or(0);
+    if max_points >= len || len <= 2 {
+        return (0..len).collect();
+    }
+    if max_points < 3 {
+        return [0, len - 1][..max_points].to_vec();
+    }
+
+    let value = |s: &[ChartDataPoint], i: usize| s.get(i).map(|p| p.value);
+    let buckets = max_points - 2;
+    let width = (len - 2) as f64 / buckets as f64;
+    let bounds = |b: usize| {
+        let start = 1 + (b as f64 * width) as usize;
+        let end = (1 + ((b + 1) as f64 * width) as usize).min(len - 1);
+        start..end
+    };
+
+    let mut keep = Vec::with_capacity(max_points);
+    keep.push(0);
+    let mut a = 0;
+    for b in 0..buckets {
+        // The third corner is the average of the next bucket, or the last
+        // point for the final bucket.
+        let next = if b + 1 < buckets {
+            bounds(b + 1)
+        } else {
+            len - 1..len
+        };
+        let cx = (next.start + next.end - 1) as f64 / 2.0;
+        let mut best = (bounds(b).start, -1.0);
+        for i in bounds(b) {
+            let area: f64 = series
+                .iter()
+                .filter_map(|s| {
+                    let ay = value(s, a)?;
+                    let y = value(s, i)?;
+                    let ys: Vec<f64> = next.clone().filter_map(|j| value(s, j)).collect();
+                    let cy = if ys.is_empty() {
+                        y
+                    } else {
+                        ys.iter().sum::<f64>() / ys.len() as f64
+                    };
+                    let (ax, x) = (a as f64, i as f64);
+                    Some(((ax - cx) * (y - ay) - (ax - x) * (cy - ay)).abs())
+                })
+                .sum();
+            if area > best.1 {
+                best = (i, area);
+            }
+        }
+        keep.push(best.0);
+        a = best.0;
+    }
+    keep.push(len - 1);
+    keep
+}
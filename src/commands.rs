@@ -1,123 +1,377 @@
-use tauri::{AppHandle, Emitter, Runtime, State};
+use serde_json::Value;
+use tauri::ipc::{CommandArg, CommandItem, CommandScope, GlobalScope, InvokeError};
+use tauri::{AppHandle, Runtime, State, Window};
 
+use crate::activity::{ActivityEntry, ActivityQuery};
 use crate::error::Error;
-use crate::models::{WidgetConfig, WidgetWindowConfig};
+use crate::limits::ActionStats;
+use crate::models::{
+    ConfigPatch, WidgetActionEvent, WidgetConfig, WidgetScope, WidgetWindowConfig,
+};
+use crate::scope::Access;
 
 #[cfg(desktop)]
 use crate::desktop::Widget;
 #[cfg(mobile)]
 use crate::mobile::Widget;
 
+/// The window calling a command and the scope entries that apply to the
+/// call.
+pub struct Caller<R: Runtime> {
+    window: Window<R>,
+    command: CommandScope<WidgetScope>,
+    global: GlobalScope<WidgetScope>,
+}
+
+impl<'a, R: Runtime> CommandArg<'a, R> for Caller<R> {
+    fn from_command(command: CommandItem<'a, R>) -> Result<Self, InvokeError> {
+        let item = || CommandItem {
+            plugin: command.plugin,
+            name: command.name,
+            key: command.key,
+            message: command.message,
+            acl: command.acl,
+        };
+        Ok(Self {
+            window: Window::from_command(item())?,
+            command: CommandScope::from_command(item())?,
+            global: GlobalScope::from_command(item())?,
+        })
+    }
+}
+
+impl<R: Runtime> Caller<R> {
+    /// Fail unless the caller's scope covers `access`.
+    fn authorize(&self, widget: &Widget<R>, access: Access<'_>) -> Result<(), Error> {
+        let window_group = widget.window_group(self.window.label());
+        let allows = self.command.allows().iter().chain(self.global.allows());
+        let denies = self.command.denies().iter().chain(self.global.denies());
+        if crate::scope::is_allowed(
+            allows.map(|e| &**e),
+            denies.map(|e| &**e),
+            window_group.as_deref(),
+            access,
+        ) {
+            Ok(())
+        } else {
+            Err(Error::Forbidden(format!(
+                "window `{}` may not access {access}",
+                self.window.label()
+            )))
+        }
+    }
+
+    /// Fail unless the caller's scope allows any group, for commands that
+    /// reach beyond one group, like reloads and action stats.
+    fn authorize_all(&self, widget: &Widget<R>) -> Result<(), Error> {
+        self.authorize(widget, Access::default())
+    }
+
+    fn authorize_group(&self, widget: &Widget<R>, group: &str) -> Result<(), Error> {
+        self.authorize(
+            widget,
+            Access {
+                group: Some(group),
+                ..Access::default()
+            },
+        )
+    }
+}
+
 #[tauri::command]
 pub fn set_items<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     key: String,
     value: String,
     group: String,
 ) -> Result<bool, Error> {
+    caller.authorize(
+        &widget,
+        Access {
+            group: Some(&group),
+            key: Some(&key),
+            ..Access::default()
+        },
+    )?;
     widget.set_items(&key, &value, &group)
 }
 
 #[tauri::command]
 pub fn get_items<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     key: String,
     group: String,
 ) -> Result<Option<String>, Error> {
+    caller.authorize(
+        &widget,
+        Access {
+            group: Some(&group),
+            key: Some(&key),
+            ..Access::default()
+        },
+    )?;
     widget.get_items(&key, &group)
 }
 
 #[tauri::command]
 pub fn set_register_widget<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     widgets: Vec<String>,
 ) -> Result<bool, Error> {
+    caller.authorize_all(&widget)?;
     widget.set_register_widget(widgets)
 }
 
 #[tauri::command]
 pub fn reload_all_timelines<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
 ) -> Result<bool, Error> {
+    caller.authorize_all(&widget)?;
     widget.reload_all_timelines()
 }
 
 #[tauri::command]
 pub fn reload_timelines<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     of_kind: String,
 ) -> Result<bool, Error> {
+    caller.authorize_all(&widget)?;
     widget.reload_timelines(&of_kind)
 }
 
 #[tauri::command]
 pub fn request_widget<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
 ) -> Result<bool, Error> {
+    caller.authorize_all(&widget)?;
     widget.request_widget()
 }
 
 #[tauri::command]
 pub async fn create_widget_window<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     config: WidgetWindowConfig,
 ) -> Result<bool, Error> {
+    caller.authorize_group(&widget, config.group.as_deref().unwrap_or("default"))?;
     widget.create_widget_window(config)
 }
 
 #[tauri::command]
 pub async fn close_widget_window<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     label: String,
 ) -> Result<bool, Error> {
+    let group = widget.window_group(&label);
+    caller.authorize(
+        &widget,
+        Access {
+            group: group.as_deref(),
+            ..Access::default()
+        },
+    )?;
     widget.close_widget_window(&label)
 }
 
 #[tauri::command]
 pub fn set_widget_config<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
-    config: WidgetConfig,
+    config: serde_json::Value,
     group: String,
     skip_reload: Option<bool>,
 ) -> Result<bool, Error> {
+    caller.authorize_group(&widget, &group)?;
     widget.set_widget_config(&config, &group, skip_reload.unwrap_or(false))
 }
 
 #[tauri::command]
 pub fn get_widget_config<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     group: String,
+    locale: Option<String>,
 ) -> Result<Option<WidgetConfig>, Error> {
-    widget.get_widget_config(&group)
+    caller.authorize_group(&widget, &group)?;
+    let locale = locale.unwrap_or_else(|| widget.locale());
+    widget.get_localized_widget_config(&group, &locale)
 }
 
 #[tauri::command]
+pub fn patch_widget_config<R: Runtime>(
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
+    group: String,
+    ops: Vec<ConfigPatch>,
+    skip_reload: Option<bool>,
+) -> Result<WidgetConfig, Error> {
+    caller.authorize_group(&widget, &group)?;
+    widget.patch_widget_config(&group, &ops, skip_reload.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn get_widget_config_schema() -> schemars::schema::RootSchema {
+    crate::schema::widget_config_schema()
+}
+
+#[tauri::command]
+pub fn lint_widget_config<R: Runtime>(
+    _app: AppHandle<R>,
+    widget: State<'_, Widget<R>>,
+    config: serde_json::Value,
+) -> Result<Vec<crate::lint::LintIssue>, Error> {
+    // Reads no stored group, so no scope applies.
+    let (config, _) = crate::resolve::resolve(&config, &widget.locale())?;
+    Ok(crate::lint::lint(&config))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn widget_action<R: Runtime>(
-    app: AppHandle<R>,
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
     action: String,
-    payload: Option<String>,
+    payload: Option<Value>,
+    group: Option<String>,
+    kind: Option<String>,
+    element_id: Option<String>,
+    nonce: Option<String>,
 ) -> Result<bool, Error> {
-    let data = serde_json::json!({ "action": action, "payload": payload });
-    app.emit("widget-action", data)
-        .map_err(|e| Error::new(format!("emit widget-action: {e}")))?;
+    caller.authorize(
+        &widget,
+        Access {
+            group: group.as_deref(),
+            action: Some(&action),
+            ..Access::default()
+        },
+    )?;
+    let mut event = WidgetActionEvent {
+        nonce,
+        group,
+        kind,
+        window: Some(caller.window.label().to_string()),
+        element_id,
+        timestamp: crate::actions::now_millis(),
+        ..WidgetActionEvent::new(action, payload)
+    };
+    // Dropped before the tap changes any state.
+    if !widget.admit_action(&mut event)? {
+        return Ok(false);
+    }
+    // Taps on a segmented control move its selection, and taps on a bound
+    // toggle flip it, before the app hears about them.
+    if let Some(group) = event.group.clone() {
+        widget.apply_tap(&group, &mut event, false)?;
+    }
+    widget.deliver_action(event)?;
     Ok(true)
 }
 
 #[tauri::command]
 pub fn poll_pending_actions<R: Runtime>(
     _app: AppHandle<R>,
+    caller: Caller<R>,
     widget: State<'_, Widget<R>>,
     group: String,
-) -> Result<Vec<serde_json::Value>, Error> {
+) -> Result<Vec<WidgetActionEvent>, Error> {
+    caller.authorize_group(&widget, &group)?;
     widget.poll_pending_actions(&group)
 }
+
+#[tauri::command]
+pub fn ack_actions<R: Runtime>(
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
+    group: String,
+    ids: Vec<String>,
+) -> Result<usize, Error> {
+    caller.authorize_group(&widget, &group)?;
+    widget.ack_actions(&group, &ids)
+}
+
+#[tauri::command]
+pub fn open_widget_url<R: Runtime>(
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
+    url: String,
+    group: Option<String>,
+    element_id: Option<String>,
+) -> Result<bool, Error> {
+    caller.authorize(
+        &widget,
+        Access {
+            group: group.as_deref(),
+            ..Access::default()
+        },
+    )?;
+    let window = Some(caller.window.label().to_string());
+    widget.open_url(&url, group, window, element_id)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn get_widget_activity<R: Runtime>(
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
+    query: Option<ActivityQuery>,
+) -> Result<Vec<ActivityEntry>, Error> {
+    let query = query.unwrap_or_default();
+    caller.authorize(
+        &widget,
+        Access {
+            group: query.group.as_deref(),
+            ..Access::default()
+        },
+    )?;
+    Ok(widget.widget_activity(&query))
+}
+
+#[tauri::command]
+pub fn export_widget_activity<R: Runtime>(
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
+    query: Option<ActivityQuery>,
+) -> Result<String, Error> {
+    let query = query.unwrap_or_default();
+    caller.authorize(
+        &widget,
+        Access {
+            group: query.group.as_deref(),
+            ..Access::default()
+        },
+    )?;
+    Ok(widget.export_widget_activity(&query))
+}
+
+#[tauri::command]
+pub fn get_action_stats<R: Runtime>(
+    _app: AppHandle<R>,
+    caller: Caller<R>,
+    widget: State<'_, Widget<R>>,
+) -> Result<ActionStats, Error> {
+    caller.authorize_all(&widget)?;
+    Ok(widget.action_stats())
+}
@@ -0,0 +1,189 @@
+//! Routing of URLs opened by desktop widgets.
+//!
+//! Buttons and links with a `url` are deep links on native widgets, where
+//! the system opens the app at that URL.  The desktop renderer hands those
+//! URLs to the plugin instead, which routes them by scheme:
+//!
+//! - URLs with one of the app's schemes, set with
+//!   `Builder::deep_link_scheme`, become [`WidgetDeepLinkEvent`]s,
+//! - `http(s)` URLs open in the default browser if they match a pattern
+//!   set with `Builder::allow_url`,
+//! - anything else is refused.
+
+use tauri::Url;
+
+use crate::error::Error;
+use crate::models::WidgetDeepLinkEvent;
+
+/// Deep-link settings, collected by the plugin builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLinks {
+    /// Schemes of the app's own URLs, without `://`.
+    pub schemes: Vec<String>,
+    /// External URLs that may be opened: exact URLs, or prefixes ending
+    /// in `*`.
+    pub allowed_urls: Vec<String>,
+    /// Label of the window focused when a deep link arrives.
+    pub window: String,
+}
+
+impl Default for DeepLinks {
+    fn default() -> Self {
+        Self {
+            schemes: Vec::new(),
+            allowed_urls: Vec::new(),
+            window: "main".into(),
+        }
+    }
+}
+
+/// Where a URL goes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    /// One of the app's own URLs, delivered as the `widget-deeplink` event.
+    App(WidgetDeepLinkEvent),
+    /// An allow-listed web page, opened in the default browser.
+    Browser(Url),
+}
+
+impl DeepLinks {
+    /// Decide where `url` goes.  Fails for malformed URLs, web pages that
+    /// are not allow-listed and other schemes.
+    pub fn route(&self, url: &str) -> crate::Result<Route> {
+        let parsed =
+            Url::parse(url).map_err(|e| Error::new(format!("invalid URL `{url}`: {e}")))?;
+        let scheme = parsed.scheme();
+        if self.schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)) {
+            return Ok(Route::App(deep_link(&parsed)));
+        }
+        match scheme {
+            "http" | "https" if self.allows(&parsed) => Ok(Route::Browser(parsed)),
+            "http" | "https" => Err(Error::Forbidden(format!(
+                "URL `{url}` is not allowed; add it with `Builder::allow_url`"
+            ))),
+            _ => Err(Error::Forbidden(format!(
+                "URL scheme `{scheme}` is neither the app's nor http(s)"
+            ))),
+        }
+    }
+
+    fn allows(&self, url: &Url) -> bool {
+        self.allowed_urls.iter().any(|pattern| allows(pattern, url))
+    }
+}
+
+/// Whether `url` matches `pattern`, an exact URL or a prefix ending in `*`.
+/// Both are compared parsed, so the scheme, host, port and credentials must
+/// be equal and `*` only extends the path: `https://example.com*` does not
+/// cover `https://example.com.evil.com/` or `https://example.com@evil.com/`.
+fn allows(pattern: &str, url: &Url) -> bool {
+    let (pattern, prefix) = match pattern.strip_suffix('*') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let Ok(pattern) = Url::parse(pattern) else {
+        return false;
+    };
+    let same_origin = pattern.scheme() == url.scheme()
+        && pattern.host_str().is_some()
+        && pattern.host_str() == url.host_str()
+        && pattern.port_or_known_default() == url.port_or_known_default()
+        && pattern.username() == url.username()
+        && pattern.password() == url.password();
+    if !same_origin {
+        return false;
+    }
+    if prefix {
+        path_and_query(url).starts_with(&path_and_query(&pattern))
+    } else {
+        path_and_query(url) == path_and_query(&pattern) && url.fragment() == pattern.fragment()
+    }
+}
+
+fn path_and_query(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    }
+}
+
+/// `myapp://settings/theme?mode=dark` and `myapp:///settings/theme?mode=dark`
+/// both have the route `settings/theme`.
+fn deep_link(url: &Url) -> WidgetDeepLinkEvent {
+    let route = format!("{}{}", url.host_str().unwrap_or_default(), url.path());
+    WidgetDeepLinkEvent {
+        url: url.to_string(),
+        route: route.trim_matches('/').to_string(),
+        params: url.query_pairs().into_owned().collect(),
+        group: None,
+        window: None,
+        element_id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(patterns: &[&str]) -> DeepLinks {
+        DeepLinks {
+            schemes: vec!["myapp".into()],
+            allowed_urls: patterns.iter().map(|p| p.to_string()).collect(),
+            ..DeepLinks::default()
+        }
+    }
+
+    fn opens(links: &DeepLinks, url: &str) -> bool {
+        matches!(links.route(url), Ok(Route::Browser(_)))
+    }
+
+    #[test]
+    fn prefix_patterns_only_extend_the_path() {
+        let links = links(&["https://example.com*", "https://docs.example.com/help/*"]);
+        assert!(opens(&links, "https://example.com"));
+        assert!(opens(&links, "https://example.com/pricing?plan=pro"));
+        assert!(opens(&links, "https://docs.example.com/help/widgets"));
+        assert!(!opens(&links, "https://docs.example.com/helpdesk"));
+        assert!(!opens(&links, "http://example.com/"));
+        assert!(!opens(&links, "https://example.com:8443/"));
+    }
+
+    #[test]
+    fn look_alike_hosts_are_refused() {
+        let links = links(&["https://example.com*"]);
+        assert!(!opens(&links, "https://example.com.evil.com/"));
+        assert!(!opens(&links, "https://example.community/"));
+        assert!(!opens(&links, "https://evil.com/https://example.com"));
+    }
+
+    #[test]
+    fn userinfo_is_refused() {
+        let links = links(&["https://example.com*", "https://example.com/"]);
+        assert!(!opens(&links, "https://example.com@evil.com/"));
+        assert!(!opens(&links, "https://example.com:pw@evil.com/"));
+        assert!(!opens(&links, "https://user@example.com/"));
+    }
+
+    #[test]
+    fn exact_patterns_match_the_whole_url() {
+        let links = links(&["https://example.com", "https://example.com/about"]);
+        assert!(opens(&links, "https://example.com/"));
+        assert!(opens(&links, "https://example.com/about"));
+        assert!(!opens(&links, "https://example.com/about/team"));
+        assert!(!opens(&links, "https://example.com/about?x=1"));
+    }
+
+    #[test]
+    fn app_schemes_become_deep_links() {
+        let links = links(&[]);
+        let Ok(Route::App(event)) = links.route("myapp://settings/theme?mode=dark") else {
+            panic!("not a deep link");
+        };
+        assert_eq!(event.route, "settings/theme");
+        assert_eq!(event.params.get("mode").map(String::as_str), Some("dark"));
+        assert!(matches!(
+            links.route("javascript:alert(1)"),
+            Err(Error::Forbidden(_))
+        ));
+    }
+}
@@ -1,22 +1,42 @@
 use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::borrow::Cow;
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::fs;
 use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tauri::{
     plugin::PluginApi, AppHandle, Emitter, Manager, Runtime, WebviewUrl, WebviewWindowBuilder,
+    WindowEvent,
 };
 
+use crate::actions::{ControlKind, Controls};
+use crate::activity::{ActivityEntry, ActivityLog, ActivityQuery};
+use crate::deeplink::{DeepLinks, Route};
 use crate::error::Error;
-use crate::models::{WidgetConfig, WidgetWindowConfig};
+use crate::handlers::{ActionContext, ActionDispatcher, QueueStore};
+use crate::limits::{ActionFilter, ActionStats};
+use crate::migrations::Migrations;
+use crate::models::{
+    ConfigPatch, WidgetActionEvent, WidgetConfig, WidgetDeepLinkEvent, WidgetTheme,
+    WidgetWindowConfig,
+};
+use crate::watcher::ActionWatcher;
 
 #[cfg(target_os = "macos")]
 use std::ffi::{CStr, CString};
 
 type DataMap = HashMap<String, String>;
 
+/// Store key under which native widgets queue their taps.
+const PENDING_ACTIONS_KEY: &str = "__widget_pending_actions__";
+
+/// File in the widgets directory listing the watched groups.  Group file
+/// names never contain `-`, so it cannot clash with a group's data file.
+const WATCHED_GROUPS_FILE: &str = "watched-groups.json";
+
 // ─── macOS: FFI (compiled from macos/WidgetReload.swift) ────
 
 #[cfg(target_os = "macos")]
@@ -35,6 +55,17 @@ extern "C" {
 /// Protocol name registered by the plugin for the built-in widget renderer.
 pub(crate) const BUILTIN_PROTOCOL: &str = "widgetview";
 
+/// The built-in renderer served by [`BUILTIN_PROTOCOL`].
+const RENDERER_HTML: &str = include_str!("../widget.html");
+
+/// Placeholder in `widget.html` replaced with the theme `<style>` block.
+const THEME_MARKER: &str = "<!-- widget-theme -->";
+
+/// The built-in renderer without any theme applied.
+pub(crate) fn renderer_html_default() -> Cow<'static, [u8]> {
+    Cow::Borrowed(RENDERER_HTML.as_bytes())
+}
+
 fn builtin_widget_url(group: &str, size: &str) -> WebviewUrl {
     #[cfg(target_os = "windows")]
     let url_str = format!(
@@ -52,24 +83,67 @@ fn builtin_widget_url(group: &str, size: &str) -> WebviewUrl {
 pub fn init<R: Runtime, C: DeserializeOwned>(
     app: &AppHandle<R>,
     _api: PluginApi<R, C>,
+    options: crate::Builder,
 ) -> crate::Result<Widget<R>> {
-    Ok(Widget {
+    let activity_file = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("widgets").join("activity.jsonl"));
+    let widget = Widget {
         app: app.clone(),
         last_config_hash: Mutex::new(0),
+        last_configs: Mutex::new(HashMap::new()),
+        controls: Mutex::new(HashMap::new()),
         store: Mutex::new(HashMap::new()),
-        #[cfg(target_os = "macos")]
-        poller_started: Mutex::new(false),
-    })
+        default_theme: options.theme,
+        migrations: options.migrations,
+        locale: options.locale,
+        window_themes: Arc::default(),
+        window_groups: Arc::default(),
+        deep_links: options.deep_links,
+        actions: ActionDispatcher::new(
+            app.clone(),
+            ActionFilter::new(options.action_limits),
+            ActivityLog::new(options.activity, activity_file),
+            options.action_retention,
+            options.manual_action_ack,
+        ),
+        watcher: Mutex::new(None),
+    };
+    widget.resume_watching();
+    Ok(widget)
 }
 
 pub struct Widget<R: Runtime> {
     app: AppHandle<R>,
     last_config_hash: Mutex<u64>,
+    /// Last config pushed to widget windows, keyed by group.  Used to emit
+    /// `widget-config-patch` events with only the changed parts.
+    last_configs: Mutex<HashMap<String, serde_json::Value>>,
+    /// Segmented controls and bound toggles of each group's config, so
+    /// that taps on other elements do not read the stored config.
+    controls: Mutex<HashMap<String, Controls>>,
     /// In-memory data store keyed by group. Eliminates race conditions on
     /// concurrent writes — the file is updated atomically under the lock.
     store: Mutex<HashMap<String, DataMap>>,
-    #[cfg(target_os = "macos")]
-    poller_started: Mutex<bool>,
+    /// Theme set on the plugin builder, used by windows without their own.
+    default_theme: Option<WidgetTheme>,
+    /// Per-window themes keyed by window label.
+    window_themes: Arc<Mutex<HashMap<String, WidgetTheme>>>,
+    /// Group of each widget window, keyed by window label.  Entries are
+    /// added once the window is built and removed when it is destroyed.
+    window_groups: Arc<Mutex<HashMap<String, String>>>,
+    /// Routing of URLs opened by widget windows.
+    deep_links: DeepLinks,
+    /// Upgrade steps applied to stored configs when they are read.
+    migrations: Migrations,
+    /// Locale set on the plugin builder, overriding the system locale.
+    locale: Option<String>,
+    /// Handlers, limits, activity log and queue of widget actions.
+    actions: ActionDispatcher<R>,
+    /// Watches data files for actions queued by native widgets.
+    watcher: Mutex<Option<ActionWatcher>>,
 }
 
 impl<R: Runtime> Widget<R> {
@@ -87,6 +161,12 @@ impl<R: Runtime> Widget<R> {
             }
         }
 
+        let dir = self.widgets_dir()?;
+        Ok(dir.join(format!("{}.json", file_stem(group))))
+    }
+
+    /// `widgets` in the app data directory, created if missing.
+    fn widgets_dir(&self) -> crate::Result<PathBuf> {
         let base = self
             .app
             .path()
@@ -96,16 +176,20 @@ impl<R: Runtime> Widget<R> {
         if !dir.exists() {
             fs::create_dir_all(&dir)?;
         }
-        let safe: String = group
-            .chars()
-            .map(|c| if c.is_alphanumeric() || c == '.' { c } else { '_' })
-            .collect();
-        Ok(dir.join(format!("{safe}.json")))
+        Ok(dir)
     }
 
     // ── Storage ──────────────────────────────────────────────────────────
 
     pub fn set_items(&self, key: &str, value: &str, group: &str) -> crate::Result<bool> {
+        self.write_item(key, value, group)?;
+        let _ = self.app.emit("widget-update", group);
+        Ok(true)
+    }
+
+    /// Store `value` without notifying widget windows, for plugin-internal
+    /// keys that do not affect rendering.
+    fn write_item(&self, key: &str, value: &str, group: &str) -> crate::Result<()> {
         let path = self.storage_path(group)?;
         let mut store = self.store.lock().unwrap();
         let map = store.entry(group.to_string()).or_insert_with(|| {
@@ -120,15 +204,23 @@ impl<R: Runtime> Widget<R> {
         });
         map.insert(key.into(), value.into());
         let json = serde_json::to_string_pretty(map)?;
-        drop(store);
+        // Written under the lock, so the file never lags behind the map
+        // that `take_native_actions` replaces with it.
         atomic_write(&path, json.as_bytes())?;
+        #[cfg(target_os = "macos")]
+        let mirror = (key != crate::queue::QUEUE_KEY).then(|| map.clone());
+        drop(store);
 
+        // WidgetKit gets a copy with the elements it cannot draw lowered;
+        // the stored config keeps them for the desktop renderer.  The action
+        // queue is the app's own and is not mirrored.
         #[cfg(target_os = "macos")]
-        {
+        if let Some(items) = mirror {
+            let native = crate::fallback::native_item(key, value);
             if let (Ok(c_group), Ok(c_key), Ok(c_value)) = (
                 CString::new(group),
                 CString::new(key),
-                CString::new(value),
+                CString::new(native.as_ref()),
             ) {
                 unsafe {
                     macos_widget_set_defaults(
@@ -138,11 +230,16 @@ impl<R: Runtime> Widget<R> {
                     );
                 }
             }
-            macos_write_to_widget_container(group, &json);
+            let mirror: HashMap<&str, Cow<str>> = items
+                .iter()
+                .filter(|(k, _)| k.as_str() != crate::queue::QUEUE_KEY)
+                .map(|(k, v)| (k.as_str(), crate::fallback::native_item(k, v)))
+                .collect();
+            if let Ok(json) = serde_json::to_string_pretty(&mirror) {
+                macos_write_to_widget_container(group, &json);
+            }
         }
-
-        let _ = self.app.emit("widget-update", group);
-        Ok(true)
+        Ok(())
     }
 
     pub fn get_items(&self, key: &str, group: &str) -> crate::Result<Option<String>> {
@@ -164,22 +261,31 @@ impl<R: Runtime> Widget<R> {
     // ── Widget windows ──────────────────────────────────────────────────
 
     pub fn create_widget_window(&self, config: WidgetWindowConfig) -> crate::Result<bool> {
+        if self.app.get_webview_window(&config.label).is_some() {
+            return Err(Error::new(format!(
+                "a window labelled `{}` already exists",
+                config.label
+            )));
+        }
         let app = self.app.clone();
         let label_log = config.label.clone();
+        let themes = self.window_themes.clone();
+        let groups = self.window_groups.clone();
 
+        let family = config.family();
+        let (width, height) = config.inner_size();
         self.app
             .run_on_main_thread(move || {
                 let url = match config.url.as_deref() {
                     Some(u) if !u.is_empty() => WebviewUrl::App(u.into()),
                     _ => {
                         let group = config.group.as_deref().unwrap_or("default");
-                        let size = config.size.as_deref().unwrap_or("small");
-                        builtin_widget_url(group, size)
+                        builtin_widget_url(group, family.key())
                     }
                 };
                 let mut builder = WebviewWindowBuilder::new(&app, &config.label, url)
                     .title("")
-                    .inner_size(config.width, config.height)
+                    .inner_size(width, height)
                     .decorations(false)
                     .skip_taskbar(config.skip_taskbar)
                     .always_on_top(config.always_on_top)
@@ -190,9 +296,31 @@ impl<R: Runtime> Widget<R> {
                     builder = builder.position(x, y);
                 }
 
-                if let Err(e) = builder.build() {
-                    log::error!("create_widget_window '{}': {}", config.label, e);
+                let window = match builder.build() {
+                    Ok(window) => window,
+                    Err(e) => {
+                        log::error!("create_widget_window '{}': {}", config.label, e);
+                        return;
+                    }
+                };
+                // Recorded only for windows built here, so a call with the
+                // label of an existing window cannot rebind its scope.  The
+                // new window's page loads through the event loop, i.e. after
+                // this closure has returned.
+                let label = config.label.clone();
+                if let Some(theme) = config.theme {
+                    themes.lock().unwrap().insert(label.clone(), theme);
                 }
+                groups.lock().unwrap().insert(
+                    label.clone(),
+                    config.group.unwrap_or_else(|| "default".into()),
+                );
+                window.on_window_event(move |event| {
+                    if let WindowEvent::Destroyed = event {
+                        themes.lock().unwrap().remove(&label);
+                        groups.lock().unwrap().remove(&label);
+                    }
+                });
             })
             .map_err(|e| Error::new(format!("main thread dispatch: {e}")))?;
 
@@ -209,6 +337,55 @@ impl<R: Runtime> Widget<R> {
         }
     }
 
+    /// Group of the widget window `label`, as given to
+    /// [`Widget::create_widget_window`].  `None` for other windows.
+    pub fn window_group(&self, label: &str) -> Option<String> {
+        self.window_groups.lock().unwrap().get(label).cloned()
+    }
+
+    /// Route `url`, opened by an element of a widget window: deliver the
+    /// app's own URLs as the `widget-deeplink` event and focus the app's
+    /// window, and open allow-listed web pages in the browser.
+    pub(crate) fn open_url(
+        &self,
+        url: &str,
+        group: Option<String>,
+        window: Option<String>,
+        element_id: Option<String>,
+    ) -> crate::Result<()> {
+        match self.deep_links.route(url)? {
+            Route::App(link) => {
+                let link = WidgetDeepLinkEvent {
+                    group,
+                    window,
+                    element_id,
+                    ..link
+                };
+                if let Some(win) = self.app.get_webview_window(&self.deep_links.window) {
+                    let _ = win.unminimize();
+                    let _ = win.show();
+                    let _ = win.set_focus();
+                }
+                self.app
+                    .emit("widget-deeplink", &link)
+                    .map_err(|e| Error::new(format!("emit widget-deeplink: {e}")))
+            }
+            Route::Browser(url) => Ok(open::that_detached(url.as_str())?),
+        }
+    }
+
+    /// HTML of the built-in renderer with the theme for `label` injected.
+    pub(crate) fn renderer_html(&self, label: &str) -> Cow<'static, [u8]> {
+        let themes = self.window_themes.lock().unwrap();
+        match themes.get(label).or(self.default_theme.as_ref()) {
+            Some(theme) => {
+                let style = format!("<style id=\"widget-theme\">{}</style>", theme_css(theme));
+                Cow::Owned(RENDERER_HTML.replacen(THEME_MARKER, &style, 1).into_bytes())
+            }
+            None => renderer_html_default(),
+        }
+    }
+
     // ── Stubs / reload ──────────────────────────────────────────────────
 
     pub fn set_register_widget(&self, _widgets: Vec<String>) -> crate::Result<bool> {
@@ -216,12 +393,18 @@ impl<R: Runtime> Widget<R> {
     }
 
     pub fn reload_all_timelines(&self) -> crate::Result<bool> {
+        let _ = self.app.emit("widget-reload", "all");
+        self.reload_native_timelines()
+    }
+
+    /// Reload the WidgetKit timelines without telling widget windows, for
+    /// changes they already receive as an event.
+    fn reload_native_timelines(&self) -> crate::Result<bool> {
         #[cfg(target_os = "macos")]
         {
             let _ = unsafe { macos_widget_reload_all() };
         }
-        let _ = self.app.emit("widget-reload", "all");
-        Ok(true)
+        self.actions.activity.reload("all", Ok(true))
     }
 
     pub fn reload_timelines(&self, of_kind: &str) -> crate::Result<bool> {
@@ -231,7 +414,7 @@ impl<R: Runtime> Widget<R> {
             let _ = unsafe { macos_widget_reload_kind(c.as_ptr()) };
         }
         let _ = self.app.emit("widget-reload", of_kind);
-        Ok(true)
+        self.actions.activity.reload(of_kind, Ok(true))
     }
 
     pub fn request_widget(&self) -> crate::Result<bool> {
@@ -242,20 +425,43 @@ impl<R: Runtime> Widget<R> {
 
     // ── Widget config ─────────────────────────────────────────────────────
 
+    /// Store `config` for `group` and push it to widget windows.
+    ///
+    /// `config` is a [`WidgetConfig`] or its raw JSON form; named styles and
+    /// design tokens are resolved and the result validated before storing.
     pub fn set_widget_config(
         &self,
-        config: &WidgetConfig,
+        config: &impl Serialize,
         group: &str,
         skip_reload: bool,
     ) -> crate::Result<bool> {
-        let val = serde_json::to_value(config)
-            .map_err(|e| Error::new(format!("serialize config: {e}")))?;
-        let compact = strip_nulls(val);
+        let source = serde_json::to_value(config)
+            .map_err(|e| Error::SerdeJson(format!("serialize config: {e}")))?;
+        self.store_widget_config(strip_nulls(source), group, skip_reload)
+            .map(|_| true)
+    }
+
+    fn store_widget_config(
+        &self,
+        mut source: serde_json::Value,
+        group: &str,
+        skip_reload: bool,
+    ) -> crate::Result<WidgetConfig> {
+        // Set by the app as of this build, so it must not be migrated when
+        // read back.
+        self.migrations.stamp(&mut source)?;
+        let toggles = crate::actions::sync_toggles(&source, |key| self.toggle_state(key, group));
+        crate::patch::apply(&mut source, &toggles)?;
+        let (config, resolved) = crate::resolve::resolve(&source, &self.locale())?;
+        let compact = strip_nulls(resolved);
         let json = serde_json::to_string(&compact)
-            .map_err(|e| Error::new(format!("serialize config: {e}")))?;
+            .map_err(|e| Error::SerdeJson(format!("serialize config: {e}")))?;
+        let source_json = serde_json::to_string(&source)
+            .map_err(|e| Error::SerdeJson(format!("serialize config: {e}")))?;
 
         let mut hasher = DefaultHasher::new();
         json.hash(&mut hasher);
+        source_json.hash(&mut hasher);
         let new_hash = hasher.finish();
 
         let changed = {
@@ -269,88 +475,478 @@ impl<R: Runtime> Widget<R> {
         };
 
         if changed {
-            self.set_items("__widget_config__", &json, group)?;
+            self.write_item("__widget_config_source__", &source_json, group)?;
+            self.write_item("__widget_config__", &json, group)?;
         }
+        self.controls
+            .lock()
+            .unwrap()
+            .insert(group.to_string(), Controls::of(&source));
 
-        let _ = self.app.emit("widget-config-push", &compact);
+        // The built-in renderer gets the changes as a patch when it has the
+        // previous config, or a `widget-update` to refetch it.
+        let previous = self
+            .last_configs
+            .lock()
+            .unwrap()
+            .insert(group.to_string(), compact.clone());
+        match previous {
+            Some(previous) => {
+                let ops = crate::patch::diff(&previous, &compact);
+                if !ops.is_empty() {
+                    let data = serde_json::json!({ "group": group, "ops": ops });
+                    let _ = self.app.emit("widget-config-patch", data);
+                }
+            }
+            None if changed => {
+                let _ = self.app.emit("widget-update", group);
+            }
+            None => {}
+        }
+        // Custom renderers keep getting the whole config, tagged with its
+        // group, and the reload.
+        if changed {
+            let mut push = compact;
+            if let Some(obj) = push.as_object_mut() {
+                obj.insert("group".into(), group.into());
+            }
+            let _ = self.app.emit("widget-config-push", push);
+        }
 
         if changed && !skip_reload {
-            self.reload_all_timelines()?;
+            let _ = self.app.emit("widget-reload", group);
+            self.reload_native_timelines()?;
         }
 
-        #[cfg(target_os = "macos")]
-        self.ensure_action_poller(group);
+        if let Err(e) = self.watch_actions(group) {
+            log::warn!("cannot watch widget actions of `{group}`: {e}");
+        }
 
-        Ok(true)
+        Ok(config)
     }
 
+    /// The resolved config of `group`, as rendered by widgets.
     pub fn get_widget_config(&self, group: &str) -> crate::Result<Option<WidgetConfig>> {
-        let raw = self.get_items("__widget_config__", group)?;
-        match raw {
-            Some(json) => {
-                let config: WidgetConfig = serde_json::from_str(&json)
-                    .map_err(|e| Error::new(format!("parse config: {e}")))?;
-                Ok(Some(config))
+        let Some(doc) = self.stored_config("__widget_config__", group)? else {
+            return Ok(None);
+        };
+        let config: WidgetConfig = serde_json::from_value(doc)
+            .map_err(|e| Error::SerdeJson(format!("parse config: {e}")))?;
+        Ok(Some(config))
+    }
+
+    /// The config of `group` with strings resolved for `locale` rather than
+    /// the current locale.  Same as [`get_widget_config`](Self::get_widget_config)
+    /// for configs without localized strings.
+    pub fn get_localized_widget_config(
+        &self,
+        group: &str,
+        locale: &str,
+    ) -> crate::Result<Option<WidgetConfig>> {
+        match self.stored_config("__widget_config_source__", group)? {
+            Some(source) if source.get("strings").is_some() => {
+                crate::resolve::resolve(&source, locale).map(|(config, _)| Some(config))
             }
-            None => Ok(None),
+            _ => self.get_widget_config(group),
         }
     }
 
-    pub fn poll_pending_actions(&self, _group: &str) -> crate::Result<Vec<serde_json::Value>> {
-        Ok(Vec::new())
+    /// Locale used to resolve localized strings: the one set on the plugin
+    /// builder, or the system locale.
+    pub fn locale(&self) -> String {
+        self.locale.clone().unwrap_or_else(crate::i18n::system_locale)
     }
 
-    // ── macOS action poller ──────────────────────────────────────────────
+    /// Read a stored config JSON of `group`, upgrading and re-saving it
+    /// when it was written with an older version.
+    fn stored_config(&self, key: &str, group: &str) -> crate::Result<Option<serde_json::Value>> {
+        let Some(raw) = self.get_items(key, group)? else {
+            return Ok(None);
+        };
+        let (doc, migrated) = self.migrations.load(&raw)?;
+        if migrated {
+            self.write_item(key, &doc.to_string(), group)?;
+        }
+        Ok(Some(doc))
+    }
 
-    #[cfg(target_os = "macos")]
-    fn ensure_action_poller(&self, group: &str) {
-        let mut started = self.poller_started.lock().unwrap();
-        if *started {
-            return;
+    /// Apply `patches` to the config of `group` as it was set (before
+    /// styles and tokens were resolved), then store it like
+    /// [`set_widget_config`](Self::set_widget_config).  Widget windows
+    /// receive the change as a `widget-config-patch` event.
+    pub fn patch_widget_config(
+        &self,
+        group: &str,
+        patches: &[ConfigPatch],
+        skip_reload: bool,
+    ) -> crate::Result<WidgetConfig> {
+        let mut doc = match self.stored_config("__widget_config_source__", group)? {
+            Some(doc) => doc,
+            None => self
+                .stored_config("__widget_config__", group)?
+                .ok_or_else(|| {
+                    Error::not_found(format!("no widget config stored for group `{group}`"))
+                })?,
+        };
+        if !self.last_configs.lock().unwrap().contains_key(group) {
+            if let Some(rendered) = self.stored_config("__widget_config__", group)? {
+                self.last_configs
+                    .lock()
+                    .unwrap()
+                    .insert(group.to_string(), rendered);
+            }
         }
-        *started = true;
 
-        let app_handle = self.app.clone();
-        let group = group.to_string();
+        crate::patch::apply(&mut doc, patches)?;
+        self.store_widget_config(doc, group, skip_reload)
+    }
 
-        std::thread::spawn(move || {
-            let data_path = match macos_shared_container(&group) {
-                Some(p) => p.join("widget_data.json"),
-                None => return,
-            };
-            loop {
-                std::thread::sleep(std::time::Duration::from_millis(500));
+    /// Move the selection of the segmented control or flip the bound toggle
+    /// that `event` tapped, before the app hears about it.  Taps on other
+    /// elements leave the config alone.  `skip_reload` is for taps on native
+    /// widgets, which already show the new state.
+    pub(crate) fn apply_tap(
+        &self,
+        group: &str,
+        event: &mut WidgetActionEvent,
+        skip_reload: bool,
+    ) -> crate::Result<()> {
+        match self.tapped_control(group, &event.action, event.element_id.as_deref())? {
+            Some(ControlKind::Selector) => {
+                if let Some(value) = event.payload.as_ref().and_then(|p| p.as_str()) {
+                    self.select_option(group, &event.action, value, skip_reload)?;
+                }
+            }
+            Some(ControlKind::Toggle) => {
+                let state = event
+                    .payload
+                    .as_ref()
+                    .and_then(crate::actions::payload_state);
+                if let Some(is_on) = self.set_toggle(group, &event.action, state, skip_reload)? {
+                    event.payload = Some(is_on.to_string().into());
+                }
+            }
+            None => {}
+        }
+        Ok(())
+    }
 
-                let map: HashMap<String, String> = match fs::read_to_string(&data_path) {
-                    Ok(s) => serde_json::from_str(&s).unwrap_or_default(),
-                    Err(_) => continue,
-                };
+    /// Kind of the control of `group` that a tap emitting `action` hit.
+    fn tapped_control(
+        &self,
+        group: &str,
+        action: &str,
+        element_id: Option<&str>,
+    ) -> crate::Result<Option<ControlKind>> {
+        if let Some(controls) = self.controls.lock().unwrap().get(group) {
+            return Ok(controls.find(action, element_id));
+        }
+        let controls = self
+            .stored_config("__widget_config_source__", group)?
+            .map(|doc| Controls::of(&doc))
+            .unwrap_or_default();
+        let kind = controls.find(action, element_id);
+        self.controls
+            .lock()
+            .unwrap()
+            .insert(group.to_string(), controls);
+        Ok(kind)
+    }
 
-                let Some(actions_str) = map.get("__widget_pending_actions__") else {
-                    continue;
-                };
+    /// Select the option with `value` in the segmented controls of `group`
+    /// that emit `action`, as if the app had patched the config with
+    /// `skip_reload`.  Returns `false` when no control changed.
+    pub fn select_option(
+        &self,
+        group: &str,
+        action: &str,
+        value: &str,
+        skip_reload: bool,
+    ) -> crate::Result<bool> {
+        let Some(doc) = self.stored_config("__widget_config_source__", group)? else {
+            return Ok(false);
+        };
+        let patches = crate::actions::select_option(&doc, action, value);
+        if patches.is_empty() {
+            return Ok(false);
+        }
+        self.patch_widget_config(group, &patches, skip_reload)
+            .map(|_| true)
+    }
 
-                let actions: Vec<String> = match serde_json::from_str(actions_str) {
-                    Ok(a) => a,
-                    Err(_) => continue,
-                };
+    /// Set the toggle of `group` that emits `action` and is bound to a
+    /// store key to `is_on`, or flip it when `None`, as if the app had
+    /// patched the config with `skip_reload`.  Returns the new state, or
+    /// `None` when no bound toggle emits `action`.
+    pub fn set_toggle(
+        &self,
+        group: &str,
+        action: &str,
+        is_on: Option<bool>,
+        skip_reload: bool,
+    ) -> crate::Result<Option<bool>> {
+        let Some(doc) = self.stored_config("__widget_config_source__", group)? else {
+            return Ok(None);
+        };
+        let current = |key: &str| self.toggle_state(key, group);
+        let Some(change) = crate::actions::set_toggle(&doc, action, is_on, current) else {
+            return Ok(None);
+        };
+        // Widget windows get the change as a patch.
+        self.write_item(&change.key, &change.is_on.to_string(), group)?;
+        if !change.patches.is_empty() {
+            self.patch_widget_config(group, &change.patches, skip_reload)?;
+        }
+        Ok(Some(change.is_on))
+    }
 
-                if actions.is_empty() {
-                    continue;
-                }
+    /// State of a toggle store key.
+    fn toggle_state(&self, key: &str, group: &str) -> Option<bool> {
+        let value = self.get_items(key, group).ok().flatten()?;
+        crate::actions::parse_state(&value)
+    }
+
+    // ── Action queue ─────────────────────────────────────────────────────
+
+    /// Queued actions of `group` that are not acknowledged yet, oldest
+    /// first.  Actions with a Rust handler are handled here, acknowledged
+    /// and left out of the result; if the handler fails they stay queued
+    /// for the next poll, until they have failed as often as the builder's
+    /// retry limit.  Without manual acknowledgement, the returned actions
+    /// count as delivered and are acknowledged too; with it they stay
+    /// queued until `ack_actions`.
+    pub fn poll_pending_actions(&self, group: &str) -> crate::Result<Vec<WidgetActionEvent>> {
+        self.actions.poll(self, group, Vec::new())
+    }
+
+    /// Remove the actions with the given ids from the queue of `group`.
+    /// Returns how many were removed.
+    pub fn ack_actions(&self, group: &str, ids: &[String]) -> crate::Result<usize> {
+        self.actions.ack(self, group, ids)
+    }
+
+    /// Run the handler of `event` or emit it as the `widget-action` event.
+    /// The action is queued first and stays queued until acknowledged or
+    /// polled, unless a handler handles it.  Actions without a group cannot
+    /// be queued.
+    pub(crate) fn deliver_action(&self, event: WidgetActionEvent) -> crate::Result<()> {
+        self.actions.deliver(self, event)
+    }
 
-                for action in &actions {
-                    let data = serde_json::json!({ "action": action });
-                    let _ = app_handle.emit("widget-action", data);
+    /// Pass `event` through the de-duplication and rate limits set on the
+    /// builder.  Returns `false` for a duplicate and a
+    /// [`Throttled`](Error::Throttled) error when a rate limit drops it.
+    pub(crate) fn admit_action(&self, event: &mut WidgetActionEvent) -> crate::Result<bool> {
+        self.actions.admit(event)
+    }
+
+    /// Counts of accepted and dropped actions since the app started.
+    pub fn action_stats(&self) -> ActionStats {
+        self.actions.filter.stats()
+    }
+
+    /// Entries of the activity log matching `query`, oldest first.
+    pub fn widget_activity(&self, query: &ActivityQuery) -> Vec<ActivityEntry> {
+        self.actions.activity.entries(query)
+    }
+
+    /// Entries of the activity log matching `query` as JSON lines.
+    pub fn export_widget_activity(&self, query: &ActivityQuery) -> String {
+        self.actions.activity.export(query)
+    }
+
+    // ── Action handlers ──────────────────────────────────────────────────
+
+    /// Run `handler` when a widget emits `action`, instead of emitting the
+    /// `widget-action` event.  The payload is deserialized into `T` as
+    /// described in [`parse_payload`](crate::handlers::parse_payload); a
+    /// payload that does not fit is reported as an error of the action.
+    /// Replaces any handler previously registered for `action`.
+    pub fn on_action<T, F>(&self, action: &str, handler: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(&ActionContext<R>, T) -> crate::Result<()> + Send + Sync + 'static,
+    {
+        self.actions.handlers.register(action, handler);
+    }
+
+    /// Remove the handler registered for `action`, so the action is emitted
+    /// as the `widget-action` event again.
+    pub fn remove_action_handler(&self, action: &str) -> bool {
+        self.actions.handlers.remove(action)
+    }
+
+    // ── Native actions ───────────────────────────────────────────────────
+
+    /// Deliver the actions that native widgets queue in the data file of
+    /// `group` as soon as they are written, including those queued while
+    /// the app was not running.  Done for every group whose config is set,
+    /// and on the next starts of the app; calling it again for a watched
+    /// group does nothing.
+    pub fn watch_actions(&self, group: &str) -> crate::Result<()> {
+        let path = self.storage_path(group)?;
+        {
+            let mut watcher = self.watcher.lock().unwrap();
+            if watcher.as_ref().is_some_and(|w| w.is_watching(group)) {
+                return Ok(());
+            }
+            let watcher = match watcher.as_mut() {
+                Some(watcher) => watcher,
+                None => {
+                    let app = self.app.clone();
+                    watcher.insert(ActionWatcher::new(move |group| {
+                        if let Some(widget) = app.try_state::<Widget<R>>() {
+                            widget.drain_native_actions(group);
+                        }
+                    })?)
                 }
+            };
+            watcher.watch(group, &path)?;
+            // Recorded under the watcher lock, which serializes the writes.
+            if let Err(e) = self.remember_watched(group) {
+                log::warn!("record watched widget group `{group}`: {e}");
+            }
+        }
+        self.drain_native_actions(group);
+        Ok(())
+    }
+
+    /// Watch every group watched before the app last exited, and every
+    /// group with a data file in the app data directory, so actions native
+    /// widgets queued meanwhile are delivered without waiting for a config.
+    fn resume_watching(&self) {
+        let groups = match self.known_groups() {
+            Ok(groups) => groups,
+            Err(e) => {
+                log::warn!("widget groups to watch: {e}");
+                return;
+            }
+        };
+        for group in groups {
+            if let Err(e) = self.watch_actions(&group) {
+                log::warn!("watch widget actions of `{group}`: {e}");
+            }
+        }
+    }
 
-                let mut cleared = map.clone();
-                cleared.insert("__widget_pending_actions__".into(), "[]".into());
-                if let Ok(json) = serde_json::to_string_pretty(&cleared) {
-                    let _ = atomic_write(&data_path, json.as_bytes());
+    /// The groups recorded by [`Widget::watch_actions`], then the names of
+    /// data files that belong to none of them.  File names are sanitized
+    /// group names, which stand in for groups stored before groups were
+    /// recorded.
+    fn known_groups(&self) -> crate::Result<Vec<String>> {
+        let dir = self.widgets_dir()?;
+        let mut groups = self.watched_groups(&dir);
+        let stems: Vec<String> = groups.iter().map(|g| file_stem(g)).collect();
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match path.file_stem().and_then(|s| s.to_str()) {
+                Some(stem) if file_stem(stem) == stem && !stems.iter().any(|s| s == stem) => {
+                    groups.push(stem.to_string());
                 }
+                _ => {}
             }
-        });
+        }
+        Ok(groups)
+    }
+
+    fn watched_groups(&self, dir: &Path) -> Vec<String> {
+        fs::read_to_string(dir.join(WATCHED_GROUPS_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn remember_watched(&self, group: &str) -> crate::Result<()> {
+        let dir = self.widgets_dir()?;
+        let mut groups = self.watched_groups(&dir);
+        if !groups.iter().any(|g| g == group) {
+            groups.push(group.to_string());
+            atomic_write(
+                &dir.join(WATCHED_GROUPS_FILE),
+                serde_json::to_string_pretty(&groups)?.as_bytes(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Stop watching data files.  Actions queued meanwhile stay in the
+    /// files and are delivered once [`Widget::watch_actions`] is called
+    /// again, which setting a config does.
+    pub fn stop_watching_actions(&self) {
+        // Dropped outside the lock, as the watcher thread may be waiting
+        // for it.
+        let watcher = self.watcher.lock().unwrap().take();
+        drop(watcher);
+    }
+
+    fn drain_native_actions(&self, group: &str) {
+        let events = match self.take_native_actions(group) {
+            Ok(events) => events,
+            Err(e) => {
+                log::warn!("widget actions of `{group}`: {e}");
+                return;
+            }
+        };
+        for mut event in events {
+            event.group = Some(group.to_string());
+            if !matches!(self.admit_action(&mut event), Ok(true)) {
+                continue;
+            }
+            // The widget intent already showed the new state; bring the
+            // plugin's copy of the config in line with it.
+            if let Err(e) = self.apply_tap(group, &mut event, true) {
+                log::warn!("widget action `{}`: {e}", event.action);
+            }
+            let action = event.action.clone();
+            if let Err(e) = self.deliver_action(event) {
+                log::warn!("widget action `{action}`: {e}");
+            }
+        }
+    }
+
+    /// Remove the actions queued by native widgets from the data file of
+    /// `group`.  Runs under the store lock, so writes through `set_items`
+    /// neither drop the actions nor bring them back.
+    fn take_native_actions(&self, group: &str) -> crate::Result<Vec<WidgetActionEvent>> {
+        let path = self.storage_path(group)?;
+        let mut store = self.store.lock().unwrap();
+        // Native widgets write the file directly, so it is newer than the
+        // copy in memory.  A file that does not parse is being written and
+        // is read again on its next change.
+        let Some(mut map) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<DataMap>(&s).ok())
+        else {
+            return Ok(Vec::new());
+        };
+        // Entries are action names, or objects with the fields of
+        // `WidgetActionEvent` for taps that carry more.
+        let pending: Vec<serde_json::Value> = map
+            .get(PENDING_ACTIONS_KEY)
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default();
+        if !pending.is_empty() {
+            map.insert(PENDING_ACTIONS_KEY.into(), "[]".into());
+            atomic_write(&path, serde_json::to_string_pretty(&map)?.as_bytes())?;
+        }
+        store.insert(group.to_string(), map);
+        Ok(pending
+            .iter()
+            .filter_map(crate::actions::pending_event)
+            .collect())
+    }
+}
+
+impl<R: Runtime> QueueStore for Widget<R> {
+    fn read_queue(&self, group: &str) -> crate::Result<Option<String>> {
+        self.get_items(crate::queue::QUEUE_KEY, group)
+    }
+
+    fn write_queue(&self, group: &str, json: &str) -> crate::Result<()> {
+        self.write_item(crate::queue::QUEUE_KEY, json, group)
     }
 }
 
@@ -390,7 +986,68 @@ fn macos_write_to_widget_container(group: &str, json: &str) {
     let _ = fs::write(&path, json.as_bytes());
 }
 
+/// Render a [`WidgetTheme`] as CSS for the built-in renderer.
+fn theme_css(theme: &WidgetTheme) -> String {
+    let mut css = String::new();
+    for font in &theme.fonts {
+        css.push_str(&format!(
+            "@font-face{{font-family:{};src:url({})",
+            css_string(&font.family),
+            css_string(&font.src)
+        ));
+        if let Some(weight) = &font.weight {
+            css.push_str(&format!(";font-weight:{weight}"));
+        }
+        if let Some(style) = &font.style {
+            css.push_str(&format!(";font-style:{style}"));
+        }
+        css.push('}');
+    }
+    if !theme.variables.is_empty() {
+        css.push_str(":root{");
+        for (name, value) in &theme.variables {
+            let name: String = name
+                .trim_start_matches("--")
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                .collect();
+            css.push_str(&format!("--{name}:{value};"));
+        }
+        css.push('}');
+    }
+    if theme.hide_close_button {
+        css.push_str("#close-btn{display:none}");
+    }
+    if theme.hide_drag_handle {
+        css.push_str("#drag-handle{display:none}");
+    }
+    if let Some(sheet) = &theme.stylesheet {
+        css.push_str(sheet);
+    }
+    // Keep user CSS from closing the surrounding <style> element.
+    css.replace("</", "<\\/")
+}
+
+/// Quote a value as a CSS string literal.
+fn css_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 /// Write data atomically: write to a temp file, then rename.
+/// Name of the data file of `group`, without extension.
+fn file_stem(group: &str) -> String {
+    group
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
 fn atomic_write(path: &PathBuf, data: &[u8]) -> std::io::Result<()> {
     let tmp = path.with_extension("tmp");
     fs::write(&tmp, data)?;
@@ -0,0 +1,1740 @@
+//! Fluent builders for widget layouts.
+//!
+//! Every element in [`models`](crate::models) has a constructor function
+//! taking its required fields and a builder with one method per optional
+//! field.  Style properties shared by all elements come from the [`Styled`]
+//! trait.  Builders convert into [`WidgetElement`] with `.into()`, and any
+//! method taking a child accepts a builder directly:
+//!
+//! ```rust,ignore
+//! use tauri_plugin_widgets::dsl::*;
+//! use tauri_plugin_widgets::models::WidgetConfig;
+//!
+//! let config: WidgetConfig = config()
+//!     .small(
+//!         vstack()
+//!             .spacing(4.)
+//!             .child(text("Hi").bold().color("#fff"))
+//!             .child(progress(0.4).tint(adaptive("#2563eb", "#60a5fa")))
+//!             .padding(8.)
+//!             .background("#0f172a"),
+//!     )
+//!     .into();
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::models::*;
+
+/// Sets an optional field from anything convertible into its type.
+macro_rules! setters {
+    ($($(#[$doc:meta])* $field:ident: $ty:ty),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            pub fn $field(mut self, $field: impl Into<$ty>) -> Self {
+                self.$field = Some($field.into());
+                self
+            }
+        )*
+    };
+}
+
+/// Adds `child` / `children` to container builders.
+macro_rules! children {
+    () => {
+        pub fn child(mut self, child: impl Into<WidgetElement>) -> Self {
+            self.children.push(child.into());
+            self
+        }
+
+        pub fn children<I>(mut self, children: I) -> Self
+        where
+            I: IntoIterator,
+            I::Item: Into<WidgetElement>,
+        {
+            self.children.extend(children.into_iter().map(Into::into));
+            self
+        }
+    };
+}
+
+/// Implements [`Styled`] for builders with a `style` field.
+macro_rules! styled {
+    ($($builder:ident),* $(,)?) => {
+        $(
+            impl Styled for $builder {
+                fn style_mut(&mut self) -> &mut ElementStyle {
+                    &mut self.style
+                }
+            }
+        )*
+    };
+}
+
+// ─── Shared style ───────────────────────────────────────────────────────────
+
+/// Style properties available on every element except `spacer`.
+pub trait Styled: Sized {
+    fn style_mut(&mut self) -> &mut ElementStyle;
+
+    fn id(mut self, id: impl Into<String>) -> Self {
+        self.style_mut().id = Some(id.into());
+        self
+    }
+
+    /// Apply a named style from [`WidgetConfig::styles`].
+    fn style(mut self, name: impl Into<String>) -> Self {
+        self.style_mut().named_style = Some(name.into());
+        self
+    }
+
+    /// Uniform padding, or per-edge padding via [`PaddingValue::Edges`].
+    fn padding(mut self, padding: impl Into<PaddingValue>) -> Self {
+        self.style_mut().padding = Some(padding.into());
+        self
+    }
+
+    fn padding_edges(self, top: f64, trailing: f64, bottom: f64, leading: f64) -> Self {
+        self.padding(PaddingValue::Edges {
+            top: Some(top),
+            bottom: Some(bottom),
+            leading: Some(leading),
+            trailing: Some(trailing),
+        })
+    }
+
+    fn background(mut self, background: impl Into<BackgroundValue>) -> Self {
+        self.style_mut().background = Some(background.into());
+        self
+    }
+
+    fn corner_radius(mut self, radius: f64) -> Self {
+        self.style_mut().corner_radius = Some(radius);
+        self
+    }
+
+    fn opacity(mut self, opacity: f64) -> Self {
+        self.style_mut().opacity = Some(opacity);
+        self
+    }
+
+    fn frame(mut self, frame: FrameConfig) -> Self {
+        self.style_mut().frame = Some(frame);
+        self
+    }
+
+    fn width(mut self, width: f64) -> Self {
+        frame_mut(self.style_mut()).width = Some(width);
+        self
+    }
+
+    fn height(mut self, height: f64) -> Self {
+        frame_mut(self.style_mut()).height = Some(height);
+        self
+    }
+
+    /// Maximum width in points, or `"infinity"` to fill the parent.
+    fn max_width(mut self, max_width: impl Into<FrameDimension>) -> Self {
+        frame_mut(self.style_mut()).max_width = Some(max_width.into());
+        self
+    }
+
+    /// Maximum height in points, or `"infinity"` to fill the parent.
+    fn max_height(mut self, max_height: impl Into<FrameDimension>) -> Self {
+        frame_mut(self.style_mut()).max_height = Some(max_height.into());
+        self
+    }
+
+    fn border(mut self, color: impl Into<String>, width: f64) -> Self {
+        self.style_mut().border = Some(BorderConfig {
+            color: color.into(),
+            width,
+        });
+        self
+    }
+
+    fn shadow(mut self, shadow: ShadowConfig) -> Self {
+        self.style_mut().shadow = Some(shadow);
+        self
+    }
+
+    fn clip_shape(mut self, shape: ClipShape) -> Self {
+        self.style_mut().clip_shape = Some(shape);
+        self
+    }
+
+    fn flex(mut self, flex: f64) -> Self {
+        self.style_mut().flex = Some(flex);
+        self
+    }
+
+    /// Text read by screen readers instead of the element's content.
+    fn accessibility_label(mut self, label: impl Into<String>) -> Self {
+        self.style_mut().accessibility_label = Some(label.into());
+        self
+    }
+
+    fn accessibility_hint(mut self, hint: impl Into<String>) -> Self {
+        self.style_mut().accessibility_hint = Some(hint.into());
+        self
+    }
+
+    /// Hide a decorative element from screen readers.
+    fn accessibility_hidden(mut self) -> Self {
+        self.style_mut().accessibility_hidden = Some(true);
+        self
+    }
+
+    fn accessibility_role(mut self, role: AccessibilityRole) -> Self {
+        self.style_mut().accessibility_role = Some(role);
+        self
+    }
+}
+
+fn frame_mut(style: &mut ElementStyle) -> &mut FrameConfig {
+    style.frame.get_or_insert_with(FrameConfig::default)
+}
+
+// ─── Value conversions ──────────────────────────────────────────────────────
+
+/// Adaptive color that switches with the system appearance.
+pub fn adaptive(light: impl Into<String>, dark: impl Into<String>) -> ColorValue {
+    ColorValue::Adaptive {
+        light: light.into(),
+        dark: dark.into(),
+    }
+}
+
+/// Linear gradient background.
+pub fn linear_gradient<I, S>(colors: I, direction: GradientDirection) -> GradientConfig
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    GradientConfig {
+        gradient_type: GradientType::Linear,
+        colors: colors.into_iter().map(Into::into).collect(),
+        direction: Some(direction),
+    }
+}
+
+impl From<&str> for ColorValue {
+    fn from(value: &str) -> Self {
+        ColorValue::Solid(value.to_string())
+    }
+}
+
+impl From<String> for ColorValue {
+    fn from(value: String) -> Self {
+        ColorValue::Solid(value)
+    }
+}
+
+impl From<&str> for BackgroundValue {
+    fn from(value: &str) -> Self {
+        BackgroundValue::Solid(value.to_string())
+    }
+}
+
+impl From<String> for BackgroundValue {
+    fn from(value: String) -> Self {
+        BackgroundValue::Solid(value)
+    }
+}
+
+impl From<ColorValue> for BackgroundValue {
+    fn from(value: ColorValue) -> Self {
+        match value {
+            ColorValue::Solid(color) => BackgroundValue::Solid(color),
+            ColorValue::Adaptive { light, dark } => BackgroundValue::Adaptive { light, dark },
+        }
+    }
+}
+
+impl From<GradientConfig> for BackgroundValue {
+    fn from(value: GradientConfig) -> Self {
+        BackgroundValue::Gradient(value)
+    }
+}
+
+impl From<f64> for PaddingValue {
+    fn from(value: f64) -> Self {
+        PaddingValue::Uniform(value)
+    }
+}
+
+impl From<f64> for FrameDimension {
+    fn from(value: f64) -> Self {
+        FrameDimension::Fixed(value)
+    }
+}
+
+impl From<&str> for FrameDimension {
+    fn from(value: &str) -> Self {
+        FrameDimension::Keyword(value.to_string())
+    }
+}
+
+impl From<&str> for LocalizedString {
+    fn from(value: &str) -> Self {
+        LocalizedString::Text(value.to_string())
+    }
+}
+
+impl From<String> for LocalizedString {
+    fn from(value: String) -> Self {
+        LocalizedString::Text(value)
+    }
+}
+
+impl From<PluralForms> for LocalizedString {
+    fn from(value: PluralForms) -> Self {
+        LocalizedString::Plural(value)
+    }
+}
+
+// ─── Config ─────────────────────────────────────────────────────────────────
+
+/// Start a [`WidgetConfig`].
+pub fn config() -> Config {
+    Config::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    version: Option<u32>,
+    small: Option<WidgetElement>,
+    medium: Option<WidgetElement>,
+    large: Option<WidgetElement>,
+    extra_large: Option<WidgetElement>,
+    accessory_circular: Option<WidgetElement>,
+    accessory_rectangular: Option<WidgetElement>,
+    accessory_inline: Option<WidgetElement>,
+    styles: BTreeMap<String, Map<String, Value>>,
+    tokens: Option<DesignTokens>,
+    components: BTreeMap<String, Component>,
+    strings: BTreeMap<String, BTreeMap<String, LocalizedString>>,
+    default_locale: Option<String>,
+}
+
+impl Config {
+    setters! {
+        version: u32,
+        small: WidgetElement,
+        medium: WidgetElement,
+        large: WidgetElement,
+        extra_large: WidgetElement,
+        accessory_circular: WidgetElement,
+        accessory_rectangular: WidgetElement,
+        accessory_inline: WidgetElement,
+        tokens: DesignTokens,
+        default_locale: String,
+    }
+
+    /// Define a named style.  `props` must be a JSON object of element
+    /// properties; other values define an empty style.
+    pub fn style(mut self, name: impl Into<String>, props: Value) -> Self {
+        let props = match props {
+            Value::Object(props) => props,
+            _ => Map::new(),
+        };
+        self.styles.insert(name.into(), props);
+        self
+    }
+
+    /// Define a component instantiated with [`component_ref`].
+    pub fn component(mut self, name: impl Into<String>, component: Component) -> Self {
+        self.components.insert(name.into(), component);
+        self
+    }
+
+    /// Add a localized string `key` for `locale`.  Accepts plain text or
+    /// [`PluralForms`].
+    pub fn string(
+        mut self,
+        locale: impl Into<String>,
+        key: impl Into<String>,
+        text: impl Into<LocalizedString>,
+    ) -> Self {
+        self.strings
+            .entry(locale.into())
+            .or_default()
+            .insert(key.into(), text.into());
+        self
+    }
+
+    pub fn build(self) -> WidgetConfig {
+        self.into()
+    }
+}
+
+impl From<Config> for WidgetConfig {
+    fn from(c: Config) -> Self {
+        WidgetConfig {
+            version: c.version.unwrap_or(1),
+            small: c.small,
+            medium: c.medium,
+            large: c.large,
+            extra_large: c.extra_large,
+            accessory_circular: c.accessory_circular,
+            accessory_rectangular: c.accessory_rectangular,
+            accessory_inline: c.accessory_inline,
+            styles: c.styles,
+            tokens: c.tokens,
+            components: c.components,
+            strings: c.strings,
+            default_locale: c.default_locale,
+        }
+    }
+}
+
+// ─── Layout containers ──────────────────────────────────────────────────────
+
+pub fn vstack() -> VStack {
+    VStack::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VStack {
+    children: Vec<WidgetElement>,
+    spacing: Option<f64>,
+    alignment: Option<HorizontalAlignment>,
+    style: ElementStyle,
+}
+
+impl VStack {
+    children!();
+    setters! { spacing: f64, alignment: HorizontalAlignment }
+}
+
+impl From<VStack> for WidgetElement {
+    fn from(b: VStack) -> Self {
+        WidgetElement::VStack {
+            children: b.children,
+            spacing: b.spacing,
+            alignment: b.alignment,
+            style: b.style,
+        }
+    }
+}
+
+pub fn hstack() -> HStack {
+    HStack::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HStack {
+    children: Vec<WidgetElement>,
+    spacing: Option<f64>,
+    alignment: Option<VerticalAlignment>,
+    style: ElementStyle,
+}
+
+impl HStack {
+    children!();
+    setters! { spacing: f64, alignment: VerticalAlignment }
+}
+
+impl From<HStack> for WidgetElement {
+    fn from(b: HStack) -> Self {
+        WidgetElement::HStack {
+            children: b.children,
+            spacing: b.spacing,
+            alignment: b.alignment,
+            style: b.style,
+        }
+    }
+}
+
+pub fn zstack() -> ZStack {
+    ZStack::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ZStack {
+    children: Vec<WidgetElement>,
+    alignment: Option<String>,
+    style: ElementStyle,
+}
+
+impl ZStack {
+    children!();
+    setters! {
+        /// e.g. `"center"`, `"topLeading"`, `"bottomTrailing"`.
+        alignment: String,
+    }
+}
+
+impl From<ZStack> for WidgetElement {
+    fn from(b: ZStack) -> Self {
+        WidgetElement::ZStack {
+            children: b.children,
+            alignment: b.alignment,
+            style: b.style,
+        }
+    }
+}
+
+pub fn grid(columns: u32) -> Grid {
+    Grid {
+        columns,
+        ..Default::default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Grid {
+    children: Vec<WidgetElement>,
+    columns: u32,
+    spacing: Option<f64>,
+    row_spacing: Option<f64>,
+    style: ElementStyle,
+}
+
+impl Grid {
+    children!();
+    setters! { spacing: f64, row_spacing: f64 }
+}
+
+impl From<Grid> for WidgetElement {
+    fn from(b: Grid) -> Self {
+        WidgetElement::Grid {
+            children: b.children,
+            columns: b.columns,
+            spacing: b.spacing,
+            row_spacing: b.row_spacing,
+            style: b.style,
+        }
+    }
+}
+
+pub fn container() -> Container {
+    Container::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Container {
+    children: Vec<WidgetElement>,
+    content_alignment: Option<String>,
+    style: ElementStyle,
+}
+
+impl Container {
+    children!();
+    setters! { content_alignment: String }
+}
+
+impl From<Container> for WidgetElement {
+    fn from(b: Container) -> Self {
+        WidgetElement::Container {
+            children: b.children,
+            content_alignment: b.content_alignment,
+            style: b.style,
+        }
+    }
+}
+
+pub fn link() -> Link {
+    Link::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Link {
+    children: Vec<WidgetElement>,
+    url: Option<String>,
+    action: Option<String>,
+    payload: Option<Value>,
+    style: ElementStyle,
+}
+
+impl Link {
+    children!();
+    setters! { url: String, action: String, payload: Value }
+}
+
+impl From<Link> for WidgetElement {
+    fn from(b: Link) -> Self {
+        WidgetElement::Link {
+            children: b.children,
+            url: b.url,
+            action: b.action,
+            payload: b.payload,
+            style: b.style,
+        }
+    }
+}
+
+// ─── Leaf elements ──────────────────────────────────────────────────────────
+
+pub fn text(content: impl Into<String>) -> Text {
+    Text {
+        content: content.into(),
+        font_size: None,
+        font_weight: None,
+        font_design: None,
+        text_style: None,
+        color: None,
+        alignment: None,
+        line_limit: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Text {
+    content: String,
+    font_size: Option<f64>,
+    font_weight: Option<FontWeight>,
+    font_design: Option<FontDesign>,
+    text_style: Option<TextStyle>,
+    color: Option<ColorValue>,
+    alignment: Option<TextAlignment>,
+    line_limit: Option<u32>,
+    style: ElementStyle,
+}
+
+impl Text {
+    setters! {
+        font_size: f64,
+        font_weight: FontWeight,
+        font_design: FontDesign,
+        text_style: TextStyle,
+        color: ColorValue,
+        alignment: TextAlignment,
+        line_limit: u32,
+    }
+
+    pub fn bold(self) -> Self {
+        self.font_weight(FontWeight::Bold)
+    }
+}
+
+impl From<Text> for WidgetElement {
+    fn from(b: Text) -> Self {
+        WidgetElement::Text {
+            content: b.content,
+            font_size: b.font_size,
+            font_weight: b.font_weight,
+            font_design: b.font_design,
+            text_style: b.text_style,
+            color: b.color,
+            alignment: b.alignment,
+            line_limit: b.line_limit,
+            style: b.style,
+        }
+    }
+}
+
+/// Text with per-span styling.  Add spans with [`RichText::span`] or parse
+/// them from markdown with [`RichText::markdown`].
+pub fn rich_text() -> RichText {
+    RichText::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RichText {
+    spans: Vec<TextSpan>,
+    markdown: Option<String>,
+    font_size: Option<f64>,
+    font_weight: Option<FontWeight>,
+    font_design: Option<FontDesign>,
+    text_style: Option<TextStyle>,
+    color: Option<ColorValue>,
+    alignment: Option<TextAlignment>,
+    line_limit: Option<u32>,
+    style: ElementStyle,
+}
+
+impl RichText {
+    setters! {
+        /// Markdown source, parsed into spans when the config is stored.
+        markdown: String,
+        font_size: f64,
+        font_weight: FontWeight,
+        font_design: FontDesign,
+        text_style: TextStyle,
+        color: ColorValue,
+        alignment: TextAlignment,
+        line_limit: u32,
+    }
+
+    /// Append a span.  Plain strings become unstyled spans.
+    pub fn span(mut self, span: impl Into<TextSpan>) -> Self {
+        self.spans.push(span.into());
+        self
+    }
+}
+
+impl From<RichText> for WidgetElement {
+    fn from(b: RichText) -> Self {
+        WidgetElement::RichText {
+            spans: b.spans,
+            markdown: b.markdown,
+            font_size: b.font_size,
+            font_weight: b.font_weight,
+            font_design: b.font_design,
+            text_style: b.text_style,
+            color: b.color,
+            alignment: b.alignment,
+            line_limit: b.line_limit,
+            style: b.style,
+        }
+    }
+}
+
+impl From<&str> for TextSpan {
+    fn from(text: &str) -> Self {
+        TextSpan {
+            text: text.to_string(),
+            ..TextSpan::default()
+        }
+    }
+}
+
+impl From<String> for TextSpan {
+    fn from(text: String) -> Self {
+        TextSpan {
+            text,
+            ..TextSpan::default()
+        }
+    }
+}
+
+pub fn image() -> Image {
+    Image::default()
+}
+
+/// Image showing an SF Symbol (Apple) or Material icon (Android).
+pub fn symbol(system_name: impl Into<String>) -> Image {
+    image().system_name(system_name)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Image {
+    system_name: Option<String>,
+    data: Option<String>,
+    url: Option<String>,
+    size: Option<f64>,
+    color: Option<ColorValue>,
+    content_mode: Option<ContentMode>,
+    style: ElementStyle,
+}
+
+impl Image {
+    setters! {
+        system_name: String,
+        /// Base64-encoded image data.
+        data: String,
+        url: String,
+        size: f64,
+        color: ColorValue,
+        content_mode: ContentMode,
+    }
+}
+
+impl From<Image> for WidgetElement {
+    fn from(b: Image) -> Self {
+        WidgetElement::Image {
+            system_name: b.system_name,
+            data: b.data,
+            url: b.url,
+            size: b.size,
+            color: b.color,
+            content_mode: b.content_mode,
+            style: b.style,
+        }
+    }
+}
+
+pub fn progress(value: f64) -> Progress {
+    Progress {
+        value,
+        total: 1.0,
+        label: None,
+        tint: None,
+        color: None,
+        bar_style: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Progress {
+    value: f64,
+    total: f64,
+    label: Option<String>,
+    tint: Option<ColorValue>,
+    color: Option<ColorValue>,
+    bar_style: Option<ProgressStyle>,
+    style: ElementStyle,
+}
+
+impl Progress {
+    setters! {
+        label: String,
+        tint: ColorValue,
+        color: ColorValue,
+        bar_style: ProgressStyle,
+    }
+
+    /// Value at which the bar is full.  Default: `1.0`.
+    pub fn total(mut self, total: f64) -> Self {
+        self.total = total;
+        self
+    }
+}
+
+impl From<Progress> for WidgetElement {
+    fn from(b: Progress) -> Self {
+        WidgetElement::Progress {
+            value: b.value,
+            total: b.total,
+            label: b.label,
+            tint: b.tint,
+            color: b.color,
+            bar_style: b.bar_style,
+            style: b.style,
+        }
+    }
+}
+
+pub fn gauge(value: f64) -> Gauge {
+    Gauge {
+        value,
+        min: None,
+        max: None,
+        label: None,
+        current_value_label: None,
+        tint: None,
+        color: None,
+        gauge_style: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Gauge {
+    value: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    label: Option<String>,
+    current_value_label: Option<String>,
+    tint: Option<ColorValue>,
+    color: Option<ColorValue>,
+    gauge_style: Option<GaugeStyle>,
+    style: ElementStyle,
+}
+
+impl Gauge {
+    setters! {
+        min: f64,
+        max: f64,
+        label: String,
+        current_value_label: String,
+        tint: ColorValue,
+        color: ColorValue,
+        gauge_style: GaugeStyle,
+    }
+}
+
+impl From<Gauge> for WidgetElement {
+    fn from(b: Gauge) -> Self {
+        WidgetElement::Gauge {
+            value: b.value,
+            min: b.min,
+            max: b.max,
+            label: b.label,
+            current_value_label: b.current_value_label,
+            tint: b.tint,
+            color: b.color,
+            gauge_style: b.gauge_style,
+            style: b.style,
+        }
+    }
+}
+
+pub fn button(label: impl Into<String>) -> Button {
+    Button {
+        label: label.into(),
+        url: None,
+        action: None,
+        payload: None,
+        color: None,
+        background_color: None,
+        font_size: None,
+        text_alignment: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Button {
+    label: String,
+    url: Option<String>,
+    action: Option<String>,
+    payload: Option<Value>,
+    color: Option<ColorValue>,
+    background_color: Option<ColorValue>,
+    font_size: Option<f64>,
+    text_alignment: Option<TextAlignment>,
+    style: ElementStyle,
+}
+
+impl Button {
+    setters! {
+        url: String,
+        action: String,
+        payload: Value,
+        color: ColorValue,
+        background_color: ColorValue,
+        font_size: f64,
+        text_alignment: TextAlignment,
+    }
+}
+
+impl From<Button> for WidgetElement {
+    fn from(b: Button) -> Self {
+        WidgetElement::Button {
+            label: b.label,
+            url: b.url,
+            action: b.action,
+            payload: b.payload,
+            color: b.color,
+            background_color: b.background_color,
+            font_size: b.font_size,
+            text_alignment: b.text_alignment,
+            style: b.style,
+        }
+    }
+}
+
+pub fn toggle(is_on: bool) -> Toggle {
+    Toggle {
+        is_on,
+        label: None,
+        tint: None,
+        action: None,
+        payload: None,
+        key: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toggle {
+    is_on: bool,
+    label: Option<String>,
+    tint: Option<String>,
+    action: Option<String>,
+    payload: Option<Value>,
+    key: Option<String>,
+    style: ElementStyle,
+}
+
+impl Toggle {
+    setters! {
+        label: String,
+        tint: String,
+        action: String,
+        payload: Value,
+        /// Bind the state to a store key, flipped by the plugin on tap.
+        key: String,
+    }
+}
+
+impl From<Toggle> for WidgetElement {
+    fn from(b: Toggle) -> Self {
+        WidgetElement::Toggle {
+            is_on: b.is_on,
+            label: b.label,
+            tint: b.tint,
+            action: b.action,
+            payload: b.payload,
+            key: b.key,
+            style: b.style,
+        }
+    }
+}
+
+pub fn segmented(action: impl Into<String>) -> Segmented {
+    Segmented {
+        action: Some(action.into()),
+        ..Segmented::default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Segmented {
+    options: Vec<SegmentOption>,
+    selected_index: Option<usize>,
+    action: Option<String>,
+    tint: Option<ColorValue>,
+    color: Option<ColorValue>,
+    font_size: Option<f64>,
+    style: ElementStyle,
+}
+
+impl Segmented {
+    setters! {
+        selected_index: usize,
+        tint: ColorValue,
+        color: ColorValue,
+        font_size: f64,
+    }
+
+    /// Add an option; `value` is sent as the action payload when tapped.
+    pub fn option(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.push(SegmentOption {
+            label: label.into(),
+            value: value.into(),
+            system_name: None,
+        });
+        self
+    }
+}
+
+impl From<Segmented> for WidgetElement {
+    fn from(b: Segmented) -> Self {
+        WidgetElement::Segmented {
+            options: b.options,
+            selected_index: b.selected_index,
+            action: b.action,
+            tint: b.tint,
+            color: b.color,
+            font_size: b.font_size,
+            style: b.style,
+        }
+    }
+}
+
+pub fn divider() -> Divider {
+    Divider::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Divider {
+    color: Option<ColorValue>,
+    thickness: Option<f64>,
+    style: ElementStyle,
+}
+
+impl Divider {
+    setters! { color: ColorValue, thickness: f64 }
+}
+
+impl From<Divider> for WidgetElement {
+    fn from(b: Divider) -> Self {
+        WidgetElement::Divider {
+            color: b.color,
+            thickness: b.thickness,
+            style: b.style,
+        }
+    }
+}
+
+pub fn spacer() -> Spacer {
+    Spacer::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Spacer {
+    min_length: Option<f64>,
+    id: Option<String>,
+}
+
+impl Spacer {
+    setters! { min_length: f64, id: String }
+}
+
+impl From<Spacer> for WidgetElement {
+    fn from(b: Spacer) -> Self {
+        WidgetElement::Spacer {
+            min_length: b.min_length,
+            id: b.id,
+        }
+    }
+}
+
+/// Date element.  `date` is an ISO 8601 string.
+pub fn date(date: impl Into<String>) -> Date {
+    Date {
+        date: date.into(),
+        date_style: None,
+        font_size: None,
+        color: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Date {
+    date: String,
+    date_style: Option<DateStyle>,
+    font_size: Option<f64>,
+    color: Option<ColorValue>,
+    style: ElementStyle,
+}
+
+impl Date {
+    setters! { date_style: DateStyle, font_size: f64, color: ColorValue }
+}
+
+impl From<Date> for WidgetElement {
+    fn from(b: Date) -> Self {
+        WidgetElement::Date {
+            date: b.date,
+            date_style: b.date_style,
+            font_size: b.font_size,
+            color: b.color,
+            style: b.style,
+        }
+    }
+}
+
+pub fn chart(chart_type: ChartType) -> Chart {
+    Chart {
+        chart_type,
+        chart_data: Vec::new(),
+        series: Vec::new(),
+        stacked: None,
+        x_axis: None,
+        y_axis: None,
+        thresholds: Vec::new(),
+        legend: None,
+        tint: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Chart {
+    chart_type: ChartType,
+    chart_data: Vec<ChartDataPoint>,
+    series: Vec<ChartSeries>,
+    stacked: Option<bool>,
+    x_axis: Option<ChartAxis>,
+    y_axis: Option<ChartAxis>,
+    thresholds: Vec<ChartThreshold>,
+    legend: Option<LegendPosition>,
+    tint: Option<ColorValue>,
+    style: ElementStyle,
+}
+
+impl Chart {
+    setters! {
+        tint: ColorValue,
+        stacked: bool,
+        x_axis: ChartAxis,
+        y_axis: ChartAxis,
+        legend: LegendPosition,
+    }
+
+    /// Add a named series.  Series colors default to the palette.
+    pub fn series(
+        mut self,
+        name: impl Into<String>,
+        data: impl IntoIterator<Item = ChartDataPoint>,
+    ) -> Self {
+        self.series.push(ChartSeries {
+            name: name.into(),
+            data: data.into_iter().collect(),
+            color: None,
+        });
+        self
+    }
+
+    /// Add a rule line; a plain number gives a dashed line without label.
+    pub fn threshold(mut self, threshold: impl Into<ChartThreshold>) -> Self {
+        self.thresholds.push(threshold.into());
+        self
+    }
+
+    pub fn point(mut self, label: impl Into<String>, value: f64) -> Self {
+        self.chart_data.push(ChartDataPoint {
+            label: label.into(),
+            value,
+            color: None,
+        });
+        self
+    }
+
+    pub fn data(mut self, points: impl IntoIterator<Item = ChartDataPoint>) -> Self {
+        self.chart_data.extend(points);
+        self
+    }
+}
+
+impl From<Chart> for WidgetElement {
+    fn from(b: Chart) -> Self {
+        WidgetElement::Chart {
+            chart_type: b.chart_type,
+            chart_data: b.chart_data,
+            series: b.series,
+            stacked: b.stacked,
+            x_axis: b.x_axis,
+            y_axis: b.y_axis,
+            thresholds: b.thresholds,
+            legend: b.legend,
+            tint: b.tint,
+            style: b.style,
+        }
+    }
+}
+
+impl From<f64> for ChartThreshold {
+    fn from(value: f64) -> Self {
+        ChartThreshold {
+            value,
+            color: None,
+            label: None,
+            dashed: None,
+        }
+    }
+}
+
+pub fn sparkline(values: impl IntoIterator<Item = f64>) -> Sparkline {
+    Sparkline {
+        values: values.into_iter().collect(),
+        sparkline_style: None,
+        highlight_last: None,
+        highlight_color: None,
+        tint: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sparkline {
+    values: Vec<f64>,
+    sparkline_style: Option<SparklineStyle>,
+    highlight_last: Option<bool>,
+    highlight_color: Option<ColorValue>,
+    tint: Option<ColorValue>,
+    style: ElementStyle,
+}
+
+impl Sparkline {
+    setters! {
+        sparkline_style: SparklineStyle,
+        highlight_color: ColorValue,
+        tint: ColorValue,
+    }
+
+    pub fn highlight_last(mut self) -> Self {
+        self.highlight_last = Some(true);
+        self
+    }
+}
+
+impl From<Sparkline> for WidgetElement {
+    fn from(b: Sparkline) -> Self {
+        WidgetElement::Sparkline {
+            values: b.values,
+            sparkline_style: b.sparkline_style,
+            highlight_last: b.highlight_last,
+            highlight_color: b.highlight_color,
+            tint: b.tint,
+            style: b.style,
+        }
+    }
+}
+
+pub fn stat(value: impl Into<String>) -> Stat {
+    Stat {
+        value: value.into(),
+        unit: None,
+        delta: None,
+        delta_unit: None,
+        lower_is_better: None,
+        caption: None,
+        font_size: None,
+        color: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Stat {
+    value: String,
+    unit: Option<String>,
+    delta: Option<f64>,
+    delta_unit: Option<String>,
+    lower_is_better: Option<bool>,
+    caption: Option<String>,
+    font_size: Option<f64>,
+    color: Option<ColorValue>,
+    style: ElementStyle,
+}
+
+impl Stat {
+    setters! {
+        unit: String,
+        delta: f64,
+        delta_unit: String,
+        caption: String,
+        font_size: f64,
+        color: ColorValue,
+    }
+
+    pub fn lower_is_better(mut self) -> Self {
+        self.lower_is_better = Some(true);
+        self
+    }
+}
+
+impl From<Stat> for WidgetElement {
+    fn from(b: Stat) -> Self {
+        WidgetElement::Stat {
+            value: b.value,
+            unit: b.unit,
+            delta: b.delta,
+            delta_unit: b.delta_unit,
+            lower_is_better: b.lower_is_better,
+            caption: b.caption,
+            font_size: b.font_size,
+            color: b.color,
+            style: b.style,
+        }
+    }
+}
+
+pub fn list() -> List {
+    List::default()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct List {
+    items: Vec<ListItem>,
+    spacing: Option<f64>,
+    font_size: Option<f64>,
+    color: Option<ColorValue>,
+    style: ElementStyle,
+}
+
+impl List {
+    setters! { spacing: f64, font_size: f64, color: ColorValue }
+
+    pub fn item(mut self, item: impl Into<ListItem>) -> Self {
+        self.items.push(item.into());
+        self
+    }
+
+    pub fn items<I>(mut self, items: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ListItem>,
+    {
+        self.items.extend(items.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl From<List> for WidgetElement {
+    fn from(b: List) -> Self {
+        WidgetElement::List {
+            items: b.items,
+            spacing: b.spacing,
+            font_size: b.font_size,
+            color: b.color,
+            style: b.style,
+        }
+    }
+}
+
+impl From<&str> for ListItem {
+    fn from(text: &str) -> Self {
+        ListItem {
+            text: text.to_string(),
+            checked: None,
+            action: None,
+            payload: None,
+        }
+    }
+}
+
+impl From<String> for ListItem {
+    fn from(text: String) -> Self {
+        text.as_str().into()
+    }
+}
+
+pub fn shape(shape_type: ShapeType) -> Shape {
+    Shape {
+        shape_type,
+        fill: None,
+        stroke: None,
+        stroke_width: None,
+        size: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Shape {
+    shape_type: ShapeType,
+    fill: Option<ColorValue>,
+    stroke: Option<ColorValue>,
+    stroke_width: Option<f64>,
+    size: Option<f64>,
+    style: ElementStyle,
+}
+
+impl Shape {
+    setters! {
+        fill: ColorValue,
+        stroke: ColorValue,
+        stroke_width: f64,
+        size: f64,
+    }
+}
+
+impl From<Shape> for WidgetElement {
+    fn from(b: Shape) -> Self {
+        WidgetElement::Shape {
+            shape_type: b.shape_type,
+            fill: b.fill,
+            stroke: b.stroke,
+            stroke_width: b.stroke_width,
+            size: b.size,
+            style: b.style,
+        }
+    }
+}
+
+/// Live timer.  `target_date` is an ISO 8601 string.
+pub fn timer(target_date: impl Into<String>) -> Timer {
+    Timer {
+        target_date: target_date.into(),
+        counting: None,
+        font_size: None,
+        font_weight: None,
+        color: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    target_date: String,
+    counting: Option<TimerCounting>,
+    font_size: Option<f64>,
+    font_weight: Option<FontWeight>,
+    color: Option<ColorValue>,
+    style: ElementStyle,
+}
+
+impl Timer {
+    setters! {
+        counting: TimerCounting,
+        font_size: f64,
+        font_weight: FontWeight,
+        color: ColorValue,
+    }
+}
+
+impl From<Timer> for WidgetElement {
+    fn from(b: Timer) -> Self {
+        WidgetElement::Timer {
+            target_date: b.target_date,
+            counting: b.counting,
+            font_size: b.font_size,
+            font_weight: b.font_weight,
+            color: b.color,
+            style: b.style,
+        }
+    }
+}
+
+pub fn label(text: impl Into<String>, system_name: impl Into<String>) -> Label {
+    Label {
+        text: text.into(),
+        system_name: system_name.into(),
+        icon_color: None,
+        font_size: None,
+        font_weight: None,
+        color: None,
+        spacing: None,
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Label {
+    text: String,
+    system_name: String,
+    icon_color: Option<ColorValue>,
+    font_size: Option<f64>,
+    font_weight: Option<FontWeight>,
+    color: Option<ColorValue>,
+    spacing: Option<f64>,
+    style: ElementStyle,
+}
+
+impl Label {
+    setters! {
+        icon_color: ColorValue,
+        font_size: f64,
+        font_weight: FontWeight,
+        color: ColorValue,
+        spacing: f64,
+    }
+}
+
+impl From<Label> for WidgetElement {
+    fn from(b: Label) -> Self {
+        WidgetElement::Label {
+            text: b.text,
+            system_name: b.system_name,
+            icon_color: b.icon_color,
+            font_size: b.font_size,
+            font_weight: b.font_weight,
+            color: b.color,
+            spacing: b.spacing,
+            style: b.style,
+        }
+    }
+}
+
+pub fn canvas(width: f64, height: f64) -> Canvas {
+    Canvas {
+        width,
+        height,
+        elements: Vec::new(),
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    width: f64,
+    height: f64,
+    elements: Vec<CanvasDrawCommand>,
+    style: ElementStyle,
+}
+
+impl Canvas {
+    /// Append a drawing command; see [`draw`] for constructors.
+    pub fn draw(mut self, command: CanvasDrawCommand) -> Self {
+        self.elements.push(command);
+        self
+    }
+}
+
+impl From<Canvas> for WidgetElement {
+    fn from(b: Canvas) -> Self {
+        WidgetElement::Canvas {
+            width: b.width,
+            height: b.height,
+            elements: b.elements,
+            style: b.style,
+        }
+    }
+}
+
+/// Component body for [`Config::component`].  Use `{{param}}` placeholders
+/// in string properties and declare them with [`Component::param`].
+pub fn component(element: impl Into<WidgetElement>) -> Component {
+    Component {
+        params: BTreeMap::new(),
+        element: serde_json::to_value(element.into()).unwrap_or(Value::Null),
+    }
+}
+
+impl Component {
+    /// Declare a parameter with its default; `Value::Null` makes it required.
+    pub fn param(mut self, name: impl Into<String>, default: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), default.into());
+        self
+    }
+}
+
+/// Instance of a component defined with [`Config::component`].
+pub fn component_ref(component: impl Into<String>) -> Ref {
+    Ref {
+        component: component.into(),
+        params: BTreeMap::new(),
+        style: ElementStyle::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Ref {
+    component: String,
+    params: BTreeMap<String, Value>,
+    style: ElementStyle,
+}
+
+impl Ref {
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+}
+
+impl From<Ref> for WidgetElement {
+    fn from(b: Ref) -> Self {
+        WidgetElement::Ref {
+            component: b.component,
+            params: b.params,
+            style: b.style,
+        }
+    }
+}
+
+styled!(
+    VStack, HStack, ZStack, Grid, Container, Link, Text, RichText, Image, Progress, Gauge, Button,
+    Toggle, Segmented, Divider, Date, Chart, Sparkline, Stat, List, Shape, Timer, Label, Canvas, Ref,
+);
+
+// ─── Canvas drawing ─────────────────────────────────────────────────────────
+
+/// Constructors for [`CanvasDrawCommand`]s.  Paint is set with the
+/// `fill`/`stroke`/`stroke_width` methods on the command.
+pub mod draw {
+    use crate::models::CanvasDrawCommand;
+
+    pub fn circle(cx: f64, cy: f64, r: f64) -> CanvasDrawCommand {
+        CanvasDrawCommand::Circle {
+            cx,
+            cy,
+            r,
+            fill: None,
+            stroke: None,
+            stroke_width: None,
+        }
+    }
+
+    pub fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> CanvasDrawCommand {
+        CanvasDrawCommand::Line {
+            x1,
+            y1,
+            x2,
+            y2,
+            stroke: None,
+            stroke_width: None,
+            line_cap: None,
+        }
+    }
+
+    pub fn rect(x: f64, y: f64, width: f64, height: f64) -> CanvasDrawCommand {
+        CanvasDrawCommand::Rect {
+            x,
+            y,
+            width,
+            height,
+            fill: None,
+            stroke: None,
+            stroke_width: None,
+            corner_radius: None,
+        }
+    }
+
+    /// Arc with angles in degrees.
+    pub fn arc(cx: f64, cy: f64, r: f64, start_angle: f64, end_angle: f64) -> CanvasDrawCommand {
+        CanvasDrawCommand::Arc {
+            cx,
+            cy,
+            r,
+            start_angle,
+            end_angle,
+            fill: None,
+            stroke: None,
+            stroke_width: None,
+        }
+    }
+
+    pub fn text(x: f64, y: f64, content: impl Into<String>) -> CanvasDrawCommand {
+        CanvasDrawCommand::Text {
+            x,
+            y,
+            content: content.into(),
+            font_size: None,
+            color: None,
+            anchor: None,
+        }
+    }
+
+    /// SVG path data, e.g. `"M10 10 L90 90"`.
+    pub fn path(d: impl Into<String>) -> CanvasDrawCommand {
+        CanvasDrawCommand::Path {
+            d: d.into(),
+            fill: None,
+            stroke: None,
+            stroke_width: None,
+        }
+    }
+}
+
+impl CanvasDrawCommand {
+    /// Fill color.  Ignored by `line` and `text`.
+    pub fn fill(mut self, color: impl Into<ColorValue>) -> Self {
+        match &mut self {
+            CanvasDrawCommand::Circle { fill, .. }
+            | CanvasDrawCommand::Rect { fill, .. }
+            | CanvasDrawCommand::Arc { fill, .. }
+            | CanvasDrawCommand::Path { fill, .. } => *fill = Some(color.into()),
+            CanvasDrawCommand::Line { .. } | CanvasDrawCommand::Text { .. } => {}
+        }
+        self
+    }
+
+    /// Stroke color (text color for `text`).
+    pub fn stroke(mut self, color: impl Into<ColorValue>) -> Self {
+        match &mut self {
+            CanvasDrawCommand::Circle { stroke, .. }
+            | CanvasDrawCommand::Line { stroke, .. }
+            | CanvasDrawCommand::Rect { stroke, .. }
+            | CanvasDrawCommand::Arc { stroke, .. }
+            | CanvasDrawCommand::Path { stroke, .. } => *stroke = Some(color.into()),
+            CanvasDrawCommand::Text { color: c, .. } => *c = Some(color.into()),
+        }
+        self
+    }
+
+    /// Stroke width.  Ignored by `text`.
+    pub fn stroke_width(mut self, width: f64) -> Self {
+        match &mut self {
+            CanvasDrawCommand::Circle { stroke_width, .. }
+            | CanvasDrawCommand::Line { stroke_width, .. }
+            | CanvasDrawCommand::Rect { stroke_width, .. }
+            | CanvasDrawCommand::Arc { stroke_width, .. }
+            | CanvasDrawCommand::Path { stroke_width, .. } => *stroke_width = Some(width),
+            CanvasDrawCommand::Text { .. } => {}
+        }
+        self
+    }
+
+    /// Corner radius of a `rect`.
+    pub fn corner_radius(mut self, radius: f64) -> Self {
+        if let CanvasDrawCommand::Rect { corner_radius, .. } = &mut self {
+            *corner_radius = Some(radius);
+        }
+        self
+    }
+
+    /// Line cap of a `line` (`"butt"`, `"round"`, `"square"`).
+    pub fn line_cap(mut self, cap: impl Into<String>) -> Self {
+        if let CanvasDrawCommand::Line { line_cap, .. } = &mut self {
+            *line_cap = Some(cap.into());
+        }
+        self
+    }
+
+    /// Font size of a `text`.
+    pub fn font_size(mut self, size: f64) -> Self {
+        if let CanvasDrawCommand::Text { font_size, .. } = &mut self {
+            *font_size = Some(size);
+        }
+        self
+    }
+
+    /// Text anchor of a `text` (`"start"`, `"middle"`, `"end"`).
+    pub fn anchor(mut self, value: impl Into<String>) -> Self {
+        if let CanvasDrawCommand::Text { anchor, .. } = &mut self {
+            *anchor = Some(value.into());
+        }
+        self
+    }
+}
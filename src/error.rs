@@ -1,9 +1,12 @@
 //! Error types for the widget plugin.
 //!
 //! Provides a unified [`Error`] enum used across desktop and mobile implementations.
-//! All variants carry a human-readable message that is serialized to JSON when
-//! returned to the webview frontend.
+//! All variants carry a human-readable message.  Errors returned to the webview
+//! frontend are serialized as `{ code, message, details }`, where `code` is one
+//! of the stable codes of [`Error::code`] and `details` lists the
+//! [`ValidationIssue`]s of validation errors.
 
+use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 
 /// Alias for `std::result::Result<T, Error>`.
@@ -23,6 +26,26 @@ pub enum Error {
     SerdeJson(String),
     /// The requested operation is not supported on the current platform.
     Unsupported(String),
+    /// The calling window's permission scope does not cover the request.
+    Forbidden(String),
+    /// A widget config, element or stored value does not exist.
+    NotFound(String),
+    /// A widget config or patch is invalid.  `message` is the full text;
+    /// `issues` locate each problem in the config.
+    Validation {
+        message: String,
+        issues: Vec<ValidationIssue>,
+    },
+    /// The request was dropped by a rate limit.
+    Throttled(String),
+}
+
+/// One problem found while validating a widget config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    /// JSON Pointer of the offending value, e.g. `/small/children/0/style`.
+    pub path: String,
+    pub message: String,
 }
 
 impl Error {
@@ -39,6 +62,41 @@ impl Error {
             operation
         ))
     }
+
+    /// Create an [`Error::NotFound`].
+    pub fn not_found(msg: impl Into<String>) -> Self {
+        Error::NotFound(msg.into())
+    }
+
+    /// Create an [`Error::Validation`] with a single issue at `path`.
+    pub fn invalid(path: impl Into<String>, msg: impl Into<String>) -> Self {
+        let message = msg.into();
+        Error::Validation {
+            issues: vec![ValidationIssue {
+                path: path.into(),
+                message: message.clone(),
+            }],
+            message,
+        }
+    }
+
+    /// Stable code of the error, for the frontend to match on:
+    /// `io`, `serde`, `unsupported`, `notFound`, `validation`,
+    /// `permissionDenied`, `throttled`, or `internal` for everything else.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::String(_) => "internal",
+            #[cfg(mobile)]
+            Error::PluginInvoke(_) => "internal",
+            Error::SerdeJson(_) => "serde",
+            Error::Unsupported(_) => "unsupported",
+            Error::Forbidden(_) => "permissionDenied",
+            Error::NotFound(_) => "notFound",
+            Error::Validation { .. } => "validation",
+            Error::Throttled(_) => "throttled",
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -50,6 +108,10 @@ impl std::fmt::Display for Error {
             Error::PluginInvoke(err) => write!(f, "Plugin invoke error: {}", err),
             Error::SerdeJson(err) => write!(f, "Serde JSON error: {}", err),
             Error::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+            Error::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            Error::NotFound(msg) => write!(f, "{}", msg),
+            Error::Validation { message, .. } => write!(f, "{}", message),
+            Error::Throttled(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -68,6 +130,13 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(desktop)]
+impl From<notify::Error> for Error {
+    fn from(err: notify::Error) -> Self {
+        Error::Io(err.to_string())
+    }
+}
+
 #[cfg(mobile)]
 impl From<tauri::plugin::mobile::PluginInvokeError> for Error {
     fn from(err: tauri::plugin::mobile::PluginInvokeError) -> Self {
@@ -92,7 +161,14 @@ impl Serialize for Error {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let mut error = serializer.serialize_struct("Error", 3)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        match self {
+            Error::Validation { issues, .. } => error.serialize_field("details", issues)?,
+            _ => error.serialize_field("details", &None::<()>)?,
+        }
+        error.end()
     }
 }
 
@@ -0,0 +1,222 @@
+//! Fallbacks for elements that only the desktop renderer draws.
+//!
+//! WidgetKit and Glance widgets are rendered by the native code shipped in
+//! the app, which knows a fixed set of element types.  Before a config is
+//! handed to them, [`lower`] rewrites the newer composite elements into
+//! ones they understand:
+//!
+//! - `sparkline` becomes an unlabelled `chart`,
+//! - `stat` becomes a `vstack` of `text` elements.
+//!
+//! Element styles (padding, frame, accessibility, …) carry over to the
+//! replacement.
+//!
+//! On macOS the desktop renderer and WidgetKit share the group's data, so
+//! only the copy mirrored to WidgetKit is lowered, with [`native_item`].
+
+use std::borrow::Cow;
+
+use serde_json::{json, Map, Value};
+
+/// Store key of the resolved config.
+const CONFIG_KEY: &str = "__widget_config__";
+
+/// Colors of improving and worsening stat deltas.
+const GOOD_COLOR: &str = "#34C759";
+const BAD_COLOR: &str = "#FF3B30";
+const SECONDARY_COLOR: &str = "secondaryLabel";
+
+/// Rewrite every `sparkline` and `stat` element in `doc`, a resolved config.
+pub fn lower(doc: &mut Value) {
+    match doc {
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                lower(value);
+            }
+            let lowered = match map.get("type").and_then(Value::as_str) {
+                Some("sparkline") => Some(sparkline(map)),
+                Some("stat") => Some(stat(map)),
+                _ => None,
+            };
+            if let Some(lowered) = lowered {
+                *map = lowered;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(lower),
+        _ => {}
+    }
+}
+
+/// `value`, stored under `key`, as native widgets read it: the resolved
+/// config with its elements lowered, anything else unchanged.
+pub fn native_item<'a>(key: &str, value: &'a str) -> Cow<'a, str> {
+    if key != CONFIG_KEY {
+        return Cow::Borrowed(value);
+    }
+    let Ok(mut doc) = serde_json::from_str::<Value>(value) else {
+        return Cow::Borrowed(value);
+    };
+    lower(&mut doc);
+    Cow::Owned(doc.to_string())
+}
+
+/// The text of a stat delta: an arrow and the magnitude, e.g. `▲ 4.2%`.
+fn delta_text(delta: f64, unit: Option<&str>) -> String {
+    let arrow = if delta > 0.0 {
+        "▲"
+    } else if delta < 0.0 {
+        "▼"
+    } else {
+        "–"
+    };
+    format!("{arrow} {}{}", delta.abs(), unit.unwrap_or_default())
+}
+
+fn sparkline(map: &mut Map<String, Value>) -> Map<String, Value> {
+    let values = take(map, "values");
+    let chart_type = take(map, "sparklineStyle");
+    let highlight = take(map, "highlightLast").as_bool().unwrap_or(false);
+    let highlight_color = take(map, "highlightColor");
+    let values = values.as_array().cloned().unwrap_or_default();
+
+    let last = values.len().saturating_sub(1);
+    let bar = chart_type == "bar";
+    let data: Vec<Value> = values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let mut point = json!({ "label": "", "value": value });
+            // Charts have no point markers, so only bars can be highlighted.
+            if bar && highlight && i == last && !highlight_color.is_null() {
+                point["color"] = highlight_color.clone();
+            }
+            point
+        })
+        .collect();
+
+    map.insert("type".into(), "chart".into());
+    map.insert(
+        "chartType".into(),
+        if chart_type.is_null() {
+            "line".into()
+        } else {
+            chart_type
+        },
+    );
+    map.insert("chartData".into(), data.into());
+    std::mem::take(map)
+}
+
+fn stat(map: &mut Map<String, Value>) -> Map<String, Value> {
+    let value = take(map, "value");
+    let unit = take(map, "unit");
+    let delta = take(map, "delta").as_f64();
+    let delta_unit = take(map, "deltaUnit");
+    let lower_is_better = take(map, "lowerIsBetter").as_bool().unwrap_or(false);
+    let caption = take(map, "caption");
+    let font_size = take(map, "fontSize").as_f64().unwrap_or(28.0);
+    let color = take(map, "color");
+
+    let mut headline = json!({
+        "type": "text",
+        "content": value,
+        "fontSize": font_size,
+        "fontWeight": "bold",
+        "lineLimit": 1,
+    });
+    if !color.is_null() {
+        headline["color"] = color;
+    }
+    let headline = if unit.is_null() {
+        headline
+    } else {
+        json!({
+            "type": "hstack",
+            "alignment": "bottom",
+            "spacing": 2,
+            "children": [headline, {
+                "type": "text",
+                "content": unit,
+                "fontSize": (font_size * 0.5).round(),
+                "color": SECONDARY_COLOR,
+                "padding": { "bottom": (font_size * 0.1).round() },
+            }],
+        })
+    };
+
+    let mut children = vec![headline];
+    if let Some(delta) = delta {
+        let good = if lower_is_better {
+            delta < 0.0
+        } else {
+            delta > 0.0
+        };
+        let color = if delta == 0.0 {
+            SECONDARY_COLOR
+        } else if good {
+            GOOD_COLOR
+        } else {
+            BAD_COLOR
+        };
+        children.push(json!({
+            "type": "text",
+            "content": delta_text(delta, delta_unit.as_str()),
+            "fontSize": 12,
+            "fontWeight": "semibold",
+            "color": color,
+        }));
+    }
+    if !caption.is_null() {
+        children.push(json!({
+            "type": "text",
+            "content": caption,
+            "fontSize": 12,
+            "color": SECONDARY_COLOR,
+            "lineLimit": 1,
+        }));
+    }
+
+    map.insert("type".into(), "vstack".into());
+    map.insert("alignment".into(), "leading".into());
+    map.insert("spacing".into(), 2.into());
+    map.insert("children".into(), children.into());
+    std::mem::take(map)
+}
+
+fn take(map: &mut Map<String, Value>, key: &str) -> Value {
+    map.remove(key).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"{"small":{"type":"vstack","children":[
+        {"type":"sparkline","values":[1,3,2],"padding":4},
+        {"type":"stat","value":"42","delta":-1.5,"caption":"today"}
+    ]}}"#;
+
+    #[test]
+    fn native_config_is_lowered() {
+        let native: Value = serde_json::from_str(&native_item(CONFIG_KEY, CONFIG)).unwrap();
+        let children = native["small"]["children"].as_array().unwrap();
+        assert_eq!(children[0]["type"], "chart");
+        assert_eq!(children[0]["chartData"].as_array().unwrap().len(), 3);
+        assert_eq!(children[0]["padding"], 4);
+        assert_eq!(children[1]["type"], "vstack");
+        assert_eq!(children[1]["children"][1]["content"], "▼ 1.5");
+    }
+
+    #[test]
+    fn other_items_are_mirrored_unchanged() {
+        assert!(matches!(
+            native_item("__widget_config_source__", CONFIG),
+            Cow::Borrowed(CONFIG)
+        ));
+        assert!(matches!(native_item("weather", "72"), Cow::Borrowed("72")));
+        assert!(matches!(
+            native_item(CONFIG_KEY, "not json"),
+            Cow::Borrowed("not json")
+        ));
+    }
+}
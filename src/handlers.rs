@@ -0,0 +1,391 @@
+//! Rust handlers for widget actions.
+//!
+//! Apps register a handler per action name with `Widget::on_action`.  When a
+//! widget emits that action, its payload is deserialized into the handler's
+//! argument type and the handler runs on the thread that received the
+//! action: the command thread for desktop widget windows, the action
+//! watcher thread for native desktop widgets, and the caller of
+//! `poll_pending_actions` on mobile.  Actions without a handler are still
+//! delivered as the `widget-action` event, so apps can move actions to Rust
+//! one at a time.
+//!
+//! [`ActionDispatcher`] admits, delivers and queues actions the same way on
+//! every platform; each platform only provides the storage of the queue.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::activity::{ActivityLog, Outcome};
+use crate::error::Error;
+use crate::limits::{ActionFilter, Verdict};
+use crate::models::WidgetActionEvent;
+use crate::queue::Retention;
+
+/// The action a handler was called for.  Derefs to the event, so
+/// `ctx.group`, `ctx.element_id` and the raw `ctx.payload` are at hand.
+pub struct ActionContext<R: Runtime> {
+    pub app: AppHandle<R>,
+    pub event: WidgetActionEvent,
+}
+
+impl<R: Runtime> Deref for ActionContext<R> {
+    type Target = WidgetActionEvent;
+
+    fn deref(&self) -> &WidgetActionEvent {
+        &self.event
+    }
+}
+
+type Handler<R> = Arc<dyn Fn(&ActionContext<R>) -> crate::Result<()> + Send + Sync>;
+
+/// Registered handlers keyed by action name.
+pub struct ActionHandlers<R: Runtime> {
+    handlers: Mutex<HashMap<String, Handler<R>>>,
+}
+
+impl<R: Runtime> Default for ActionHandlers<R> {
+    fn default() -> Self {
+        Self {
+            handlers: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<R: Runtime> ActionHandlers<R> {
+    /// Register `handler` for `action`, replacing any previous handler.
+    pub fn register<T, F>(&self, action: &str, handler: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(&ActionContext<R>, T) -> crate::Result<()> + Send + Sync + 'static,
+    {
+        let handler: Handler<R> = Arc::new(move |ctx| {
+            let payload = parse_payload(ctx.payload.as_ref()).map_err(|e| {
+                Error::new(format!("action `{}`: invalid payload: {e}", ctx.action))
+            })?;
+            handler(ctx, payload)
+        });
+        self.handlers
+            .lock()
+            .unwrap()
+            .insert(action.to_string(), handler);
+    }
+
+    /// Remove the handler for `action`.  Returns `false` when there was none.
+    pub fn remove(&self, action: &str) -> bool {
+        self.handlers.lock().unwrap().remove(action).is_some()
+    }
+
+    /// Run the handler for `ctx.action`.  Returns `false` when there is none.
+    pub fn dispatch(&self, ctx: &ActionContext<R>) -> crate::Result<bool> {
+        // Not called under the lock, so handlers may register others.
+        let handler = self.handlers.lock().unwrap().get(&ctx.action).cloned();
+        match handler {
+            Some(handler) => handler(ctx).map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Storage of the action queues, one JSON array per group.
+pub(crate) trait QueueStore {
+    fn read_queue(&self, group: &str) -> crate::Result<Option<String>>;
+    fn write_queue(&self, group: &str, json: &str) -> crate::Result<()>;
+}
+
+/// Admits, delivers and queues widget actions.
+pub(crate) struct ActionDispatcher<R: Runtime> {
+    app: AppHandle<R>,
+    /// Rust handlers registered with `Widget::on_action`.
+    pub(crate) handlers: ActionHandlers<R>,
+    /// De-duplication and rate limits of incoming actions.
+    pub(crate) filter: ActionFilter,
+    /// Actions and reloads, for finding out why a tap did nothing.
+    pub(crate) activity: ActivityLog,
+    retention: Retention,
+    /// Whether actions returned by `poll` stay queued until `ack_actions`.
+    manual_ack: bool,
+    /// Serializes read-modify-write cycles of the queues.
+    queue_lock: Mutex<()>,
+}
+
+impl<R: Runtime> ActionDispatcher<R> {
+    pub(crate) fn new(
+        app: AppHandle<R>,
+        filter: ActionFilter,
+        activity: ActivityLog,
+        retention: Retention,
+        manual_ack: bool,
+    ) -> Self {
+        Self {
+            app,
+            handlers: ActionHandlers::default(),
+            filter,
+            activity,
+            retention,
+            manual_ack,
+            queue_lock: Mutex::new(()),
+        }
+    }
+
+    /// Pass `event` through the de-duplication and rate limits set on the
+    /// builder.  Returns `false` for a duplicate and a
+    /// [`Throttled`](Error::Throttled) error when a rate limit drops it.
+    pub(crate) fn admit(&self, event: &mut WidgetActionEvent) -> crate::Result<bool> {
+        match self.filter.check(event) {
+            Verdict::Accept => Ok(true),
+            Verdict::Duplicate => {
+                self.activity.action(event, Outcome::Duplicate);
+                Ok(false)
+            }
+            Verdict::RateLimited => {
+                self.activity.action(event, Outcome::RateLimited);
+                Err(Error::Throttled(format!(
+                    "widget action `{}` exceeds its rate limit",
+                    event.action
+                )))
+            }
+        }
+    }
+
+    /// Run the handler of `event` or emit it as the `widget-action` event.
+    /// The action is queued first, so one emitted while nothing listens is
+    /// not lost: it stays queued until acknowledged or returned by `poll`.
+    /// Handled actions are acknowledged at once; an action whose handler
+    /// fails stays queued for a retry.  Actions without a group cannot be
+    /// queued.
+    pub(crate) fn deliver(
+        &self,
+        store: &impl QueueStore,
+        event: WidgetActionEvent,
+    ) -> crate::Result<()> {
+        let logged = event.clone();
+        match self.try_deliver(store, event) {
+            Ok(outcome) => {
+                self.activity.action(&logged, outcome);
+                Ok(())
+            }
+            Err(e) => {
+                self.activity.action_failed(&logged, &e);
+                Err(e)
+            }
+        }
+    }
+
+    fn try_deliver(
+        &self,
+        store: &impl QueueStore,
+        event: WidgetActionEvent,
+    ) -> crate::Result<Outcome> {
+        let event = match event.group.clone() {
+            Some(group) => {
+                let mut queued = self.enqueue(store, &group, vec![event])?;
+                queued.remove(0)
+            }
+            None => event,
+        };
+        let handled = match self.run_handler(&event) {
+            Ok(handled) => handled,
+            Err(e) => {
+                self.retry_later(store, event, &e)?;
+                return Err(e);
+            }
+        };
+        if handled {
+            if let (Some(group), Some(id)) = (&event.group, &event.id) {
+                self.ack(store, group, std::slice::from_ref(id))?;
+            }
+            return Ok(Outcome::Handled);
+        }
+        self.app
+            .emit("widget-action", &event)
+            .map_err(|e| Error::new(format!("emit widget-action: {e}")))?;
+        Ok(Outcome::Emitted)
+    }
+
+    /// Queued actions of `group` that are not acknowledged yet, oldest
+    /// first, after queuing `incoming`.  Actions with a Rust handler are
+    /// handled here, acknowledged and left out of the result; if the
+    /// handler fails they stay queued for the next poll, until they have
+    /// failed as often as the builder's retry limit.  Without manual
+    /// acknowledgement, the returned actions count as delivered and are
+    /// acknowledged too; with it they stay queued until `ack`.
+    pub(crate) fn poll(
+        &self,
+        store: &impl QueueStore,
+        group: &str,
+        incoming: Vec<WidgetActionEvent>,
+    ) -> crate::Result<Vec<WidgetActionEvent>> {
+        let pending = {
+            let _guard = self.queue_lock.lock().unwrap();
+            let mut queue = self.load(store, group)?;
+            let len = queue.len();
+            crate::queue::prune(&mut queue, self.retention);
+            let arrived = !incoming.is_empty();
+            for event in incoming {
+                crate::queue::push(&mut queue, event, self.retention);
+            }
+            if arrived || queue.len() != len {
+                self.save(store, group, &queue)?;
+            }
+            queue
+        };
+        let mut unhandled = Vec::new();
+        let mut delivered = Vec::new();
+        let mut failed = Vec::new();
+        for event in pending {
+            match self.run_handler(&event) {
+                Ok(true) => {
+                    self.activity.action(&event, Outcome::Handled);
+                    delivered.extend(event.id.clone());
+                }
+                Ok(false) => {
+                    if !self.manual_ack {
+                        delivered.extend(event.id.clone());
+                    }
+                    unhandled.push(event);
+                }
+                Err(e) => {
+                    log::warn!("widget action `{}`: {e}", event.action);
+                    self.activity.action_failed(&event, &e);
+                    failed.extend(event.id.clone().map(|id| (id, e)));
+                }
+            }
+        }
+        if !delivered.is_empty() || !failed.is_empty() {
+            let _guard = self.queue_lock.lock().unwrap();
+            let mut queue = self.load(store, group)?;
+            crate::queue::ack(&mut queue, &delivered);
+            for (id, error) in failed {
+                if let Some(event) = crate::queue::fail(&mut queue, &id, self.retention) {
+                    self.give_up(&event, &error);
+                }
+            }
+            self.save(store, group, &queue)?;
+        }
+        Ok(unhandled)
+    }
+
+    /// Remove the actions with the given ids from the queue of `group`.
+    /// Returns how many were removed.
+    pub(crate) fn ack(
+        &self,
+        store: &impl QueueStore,
+        group: &str,
+        ids: &[String],
+    ) -> crate::Result<usize> {
+        let _guard = self.queue_lock.lock().unwrap();
+        let mut queue = self.load(store, group)?;
+        let removed = crate::queue::ack(&mut queue, ids);
+        if removed > 0 {
+            self.save(store, group, &queue)?;
+        }
+        Ok(removed)
+    }
+
+    /// Run the handler registered for the action of `event`.  Returns
+    /// `false` when there is none and the event should be emitted instead.
+    pub(crate) fn run_handler(&self, event: &WidgetActionEvent) -> crate::Result<bool> {
+        self.handlers.dispatch(&ActionContext {
+            app: self.app.clone(),
+            event: event.clone(),
+        })
+    }
+
+    /// Queue `events` with fresh ids.  Returns them as queued.
+    fn enqueue(
+        &self,
+        store: &impl QueueStore,
+        group: &str,
+        events: Vec<WidgetActionEvent>,
+    ) -> crate::Result<Vec<WidgetActionEvent>> {
+        let _guard = self.queue_lock.lock().unwrap();
+        let mut queue = self.load(store, group)?;
+        let queued = events
+            .into_iter()
+            .map(|event| crate::queue::push(&mut queue, event, self.retention))
+            .collect();
+        self.save(store, group, &queue)?;
+        Ok(queued)
+    }
+
+    /// Count the failed first run of the handler of `event`, and queue the
+    /// action for `poll` to retry unless that was its last attempt.
+    fn retry_later(
+        &self,
+        store: &impl QueueStore,
+        event: WidgetActionEvent,
+        error: &Error,
+    ) -> crate::Result<()> {
+        let Some(group) = event.group.clone() else {
+            return Ok(());
+        };
+        let _guard = self.queue_lock.lock().unwrap();
+        let mut queue = self.load(store, &group)?;
+        let id = match &event.id {
+            Some(id) => id.clone(),
+            None => crate::queue::push(&mut queue, event, self.retention)
+                .id
+                .unwrap_or_default(),
+        };
+        if let Some(event) = crate::queue::fail(&mut queue, &id, self.retention) {
+            self.give_up(&event, error);
+        }
+        self.save(store, &group, &queue)
+    }
+
+    /// Record that `event` is dropped after its handler failed too often.
+    fn give_up(&self, event: &WidgetActionEvent, error: &Error) {
+        let attempts = event.attempts.unwrap_or(1);
+        log::warn!(
+            "widget action `{}` dropped after {attempts} failed attempts: {error}",
+            event.action
+        );
+        let error = Error::new(format!("dropped after {attempts} failed attempts: {error}"));
+        self.activity.action_failed(event, &error);
+    }
+
+    fn load(&self, store: &impl QueueStore, group: &str) -> crate::Result<Vec<WidgetActionEvent>> {
+        Ok(store
+            .read_queue(group)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default())
+    }
+
+    fn save(
+        &self,
+        store: &impl QueueStore,
+        group: &str,
+        queue: &[WidgetActionEvent],
+    ) -> crate::Result<()> {
+        let json = serde_json::to_string(queue)?;
+        store.write_queue(group, &json)
+    }
+}
+
+/// Deserialize an action payload into `T`.
+///
+/// JSON payloads are deserialized as they are.  String payloads holding
+/// JSON, as older configs send them, are parsed first, so `"42"` becomes a
+/// number and `"{\"id\":1}"` a struct; when `T` does not accept that, the
+/// string itself is used, so a `String` handler still receives `"42"`.  A
+/// missing payload is `null`: use `()` or `Option<T>` for actions without
+/// one.
+pub fn parse_payload<T: DeserializeOwned>(payload: Option<&Value>) -> serde_json::Result<T> {
+    let raw = match payload {
+        None => return serde_json::from_value(Value::Null),
+        Some(Value::String(raw)) => raw,
+        Some(value) => return serde_json::from_value(value.clone()),
+    };
+    let text = || serde_json::from_value(Value::String(raw.to_string()));
+    match serde_json::from_str::<Value>(raw) {
+        // Report the JSON error, which is the more useful one for payloads
+        // meant as structs.
+        Ok(value) => serde_json::from_value(value).or_else(|e| text().map_err(|_| e)),
+        Err(_) => text(),
+    }
+}
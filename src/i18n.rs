@@ -0,0 +1,145 @@
+//! Locale helpers for localized widget strings.
+//!
+//! Strings in [`WidgetConfig::strings`](crate::models::WidgetConfig::strings)
+//! are looked up along a fallback chain (`de-AT` → `de` → default locale →
+//! `en`) and plural forms are picked with simplified CLDR plural rules.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// The locale used to resolve strings when none is given: the system
+/// locale, or `en` when it cannot be determined.
+pub fn system_locale() -> String {
+    sys_locale::get_locale()
+        .map(|l| normalize(&l))
+        .unwrap_or_else(|| "en".into())
+}
+
+/// Normalize a locale tag: `de_AT.UTF-8` → `de-at`.
+pub fn normalize(locale: &str) -> String {
+    locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-")
+        .to_ascii_lowercase()
+}
+
+/// Locales to try, most specific first: every prefix of `locale`, then of
+/// `default`, then `en`.
+pub fn fallback_chain(locale: &str, default: Option<&str>) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    for tag in [Some(locale), default, Some("en")].into_iter().flatten() {
+        let tag = normalize(tag);
+        let parts: Vec<&str> = tag.split('-').filter(|p| !p.is_empty()).collect();
+        for len in (1..=parts.len()).rev() {
+            let candidate = parts[..len].join("-");
+            if !chain.contains(&candidate) {
+                chain.push(candidate);
+            }
+        }
+    }
+    chain
+}
+
+/// CLDR plural category of `n` for the language of `locale`.
+///
+/// Covers the common rule families; unknown languages use the English
+/// rule (`one` for exactly 1, `other` otherwise).
+pub fn plural_category(locale: &str, n: f64) -> &'static str {
+    let locale = normalize(locale);
+    let lang = locale.split('-').next().unwrap_or_default();
+    let integer = n.fract() == 0.0;
+    let i = n.abs().trunc() as u64;
+    let (m10, m100) = (i % 10, i % 100);
+    match lang {
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "my" | "km" | "lo" => "other",
+        "fr" | "pt" | "hi" | "bn" | "fa" | "zu" | "am" => {
+            if i <= 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+        "ru" | "uk" | "be" => {
+            if !integer {
+                "other"
+            } else if m10 == 1 && m100 != 11 {
+                "one"
+            } else if (2..=4).contains(&m10) && !(12..=14).contains(&m100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "pl" => {
+            if !integer {
+                "other"
+            } else if i == 1 {
+                "one"
+            } else if (2..=4).contains(&m10) && !(12..=14).contains(&m100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "cs" | "sk" => {
+            if !integer {
+                "many"
+            } else if i == 1 {
+                "one"
+            } else if (2..=4).contains(&i) {
+                "few"
+            } else {
+                "other"
+            }
+        }
+        "ar" => {
+            if !integer {
+                "other"
+            } else if i == 0 {
+                "zero"
+            } else if i == 1 {
+                "one"
+            } else if i == 2 {
+                "two"
+            } else if (3..=10).contains(&m100) {
+                "few"
+            } else if (11..=99).contains(&m100) {
+                "many"
+            } else {
+                "other"
+            }
+        }
+        _ => {
+            if n == 1.0 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+    }
+}
+
+/// Replace `{name}` placeholders in `template` with values from `args`.
+/// Returns the name of the first missing argument on failure.
+pub fn format(template: &str, args: &BTreeMap<String, Value>) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = rest[start + 1..start + len].trim();
+        out.push_str(&rest[..start]);
+        match args.get(name) {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(other) => out.push_str(&other.to_string()),
+            None => return Err(name.to_string()),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
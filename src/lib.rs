@@ -78,31 +78,60 @@
 //!     w.reload_all_timelines().unwrap();
 //! }
 //! ```
+//!
+//! Handle widget actions in Rust with typed payloads:
+//!
+//! ```rust,ignore
+//! app.widget().on_action("select-city", |ctx, city: String| {
+//!     ctx.app.widget().set_items("city", &city, "group.com.example.myapp")?;
+//!     Ok(())
+//! });
+//! ```
 
 use tauri::{
-    plugin::{Builder, TauriPlugin},
+    plugin::{Builder as PluginBuilder, TauriPlugin},
     Manager, Runtime,
 };
 
-#[cfg(desktop)]
-use std::borrow::Cow;
-
 #[cfg(desktop)]
 pub mod desktop;
 #[cfg(mobile)]
 pub mod mobile;
 
+pub mod actions;
+pub mod activity;
+pub mod charts;
 mod commands;
+pub mod deeplink;
+pub mod dsl;
 pub mod error;
+pub mod fallback;
+pub mod handlers;
+pub mod i18n;
+pub mod limits;
+pub mod lint;
+pub mod markdown;
+pub mod migrations;
 pub mod models;
+pub mod patch;
+pub mod queue;
+pub mod resolve;
+pub mod schema;
+pub mod scope;
+#[cfg(desktop)]
+mod watcher;
 
-pub use error::{Error, Result};
+pub use error::{Error, Result, ValidationIssue};
+pub use handlers::ActionContext;
 
 #[cfg(desktop)]
 pub use desktop::Widget;
 #[cfg(mobile)]
 pub use mobile::Widget;
 
+use migrations::Migrations;
+use models::WidgetTheme;
+
 /// Extension trait for convenient access from any Tauri manager.
 pub trait WidgetExt<R: Runtime> {
     fn widget(&self) -> &Widget<R>;
@@ -114,43 +143,239 @@ impl<R: Runtime, T: Manager<R>> WidgetExt<R> for T {
     }
 }
 
-/// Initialize the widgets plugin. Register it with `tauri::Builder::plugin()`.
+/// Builder for configuring the widgets plugin before registering it.
+///
+/// ```rust,ignore
+/// use tauri_plugin_widgets::models::WidgetTheme;
+///
+/// tauri::Builder::default()
+///     .plugin(
+///         tauri_plugin_widgets::Builder::new()
+///             .theme(WidgetTheme {
+///                 hide_drag_handle: true,
+///                 ..Default::default()
+///             })
+///             .build(),
+///     )
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    #[cfg_attr(mobile, allow(dead_code))]
+    pub(crate) theme: Option<WidgetTheme>,
+    pub(crate) migrations: Migrations,
+    pub(crate) locale: Option<String>,
+    pub(crate) action_retention: queue::Retention,
+    pub(crate) manual_action_ack: bool,
+    pub(crate) action_limits: limits::Limits,
+    #[cfg_attr(mobile, allow(dead_code))]
+    pub(crate) deep_links: deeplink::DeepLinks,
+    pub(crate) activity: activity::ActivitySettings,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default theme for the built-in desktop renderer.  Individual windows
+    /// can override it via [`WidgetWindowConfig::theme`](models::WidgetWindowConfig::theme).
+    /// Has no effect on mobile.
+    pub fn theme(mut self, theme: WidgetTheme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Locale used to resolve localized strings instead of the system
+    /// locale, e.g. when the app has its own language setting.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Limits of the action queue, which keeps actions until the app
+    /// acknowledges them with `ack_actions`.  Defaults to 500 actions kept
+    /// for a week.
+    pub fn action_retention(mut self, max_actions: usize, max_age: std::time::Duration) -> Self {
+        self.action_retention = queue::Retention {
+            max_actions,
+            max_age,
+            ..self.action_retention
+        };
+        self
+    }
+
+    /// Keep actions returned by `poll_pending_actions` queued until the app
+    /// acknowledges them with `ack_actions`, instead of acknowledging them
+    /// once returned.  Actions emitted as the `widget-action` event stay
+    /// queued until acknowledged or polled either way.
+    pub fn manual_action_ack(mut self, manual: bool) -> Self {
+        self.manual_action_ack = manual;
+        self
+    }
+
+    /// How often a failing Rust action handler runs for the same action
+    /// before the action is dropped and the failure recorded in the
+    /// activity log.  Defaults to 3.
+    pub fn action_retry_limit(mut self, attempts: u32) -> Self {
+        self.action_retention.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Treat URLs with `scheme` (e.g. `"myapp"`) opened by desktop widgets
+    /// as the app's deep links: they are delivered as the `widget-deeplink`
+    /// event, with the URL split into route and params, and the app's
+    /// window is focused.  Native widgets open them through the system.
+    pub fn deep_link_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.deep_links.schemes.push(scheme.into());
+        self
+    }
+
+    /// Let desktop widgets open web pages matching `pattern` in the default
+    /// browser: an exact URL, or a prefix ending in `*` such as
+    /// `"https://example.com/*"`.  The scheme, host and port must match
+    /// exactly; `*` only extends the path.  Other web pages are refused.
+    pub fn allow_url(mut self, pattern: impl Into<String>) -> Self {
+        self.deep_links.allowed_urls.push(pattern.into());
+        self
+    }
+
+    /// Label of the window focused when a desktop widget opens a deep
+    /// link.  Defaults to `"main"`.
+    pub fn deep_link_window(mut self, label: impl Into<String>) -> Self {
+        self.deep_links.window = label.into();
+        self
+    }
+
+    /// Number of actions and reloads kept in the activity log.  Defaults
+    /// to 200; zero turns the log off.
+    pub fn activity_log(mut self, capacity: usize) -> Self {
+        self.activity.capacity = capacity;
+        self
+    }
+
+    /// Keep the activity log in `widgets/activity.jsonl` in the app data
+    /// directory, so it survives restarts.  Off by default.
+    pub fn persist_activity_log(mut self, persist: bool) -> Self {
+        self.activity.persist = persist;
+        self
+    }
+
+    /// How long an accepted action suppresses equal actions, i.e. with the
+    /// same group, action, element, window and payload, and retries with
+    /// its nonce.  Taps in two widget windows of a group are not equal.
+    /// Defaults to 500 ms, which absorbs double-clicks; zero turns
+    /// de-duplication off.
+    pub fn action_dedup_window(mut self, window: std::time::Duration) -> Self {
+        self.action_limits.dedup_window = window;
+        self
+    }
+
+    /// Accept at most `max` actions named `action` every `per` from each
+    /// group; the rest are dropped and counted in `action_stats`.
+    ///
+    /// ```rust,ignore
+    /// tauri_plugin_widgets::Builder::new()
+    ///     // One refresh every two seconds is plenty.
+    ///     .action_rate_limit("refresh", 1, Duration::from_secs(2))
+    ///     .default_action_rate_limit(10, Duration::from_secs(1))
+    ///     .build()
+    /// ```
+    pub fn action_rate_limit(
+        mut self,
+        action: impl Into<String>,
+        max: u32,
+        per: std::time::Duration,
+    ) -> Self {
+        self.action_limits
+            .rate_limits
+            .insert(action.into(), limits::RateLimit { max, per });
+        self
+    }
+
+    /// Rate limit of actions without one of their own.  None by default.
+    pub fn default_action_rate_limit(mut self, max: u32, per: std::time::Duration) -> Self {
+        self.action_limits.default_rate_limit = Some(limits::RateLimit { max, per });
+        self
+    }
+
+    /// Register a step that upgrades stored configs from version `from` to
+    /// `from + 1`.  Stored configs are upgraded when they are read; configs
+    /// newer than the last registered step are rejected.
+    ///
+    /// ```rust,ignore
+    /// tauri_plugin_widgets::Builder::new()
+    ///     // v2 renamed the `subtitle` text element id to `caption`.
+    ///     .migration(1, |config| {
+    ///         if let Some(small) = config.get_mut("small") {
+    ///             rename_ids(small, "subtitle", "caption");
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn migration<F>(mut self, from: u32, migration: F) -> Self
+    where
+        F: Fn(&mut serde_json::Value) -> crate::Result<()> + Send + Sync + 'static,
+    {
+        self.migrations.register(from, migration);
+        self
+    }
+
+    /// Build the plugin. Register it with `tauri::Builder::plugin()`.
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        let builder = PluginBuilder::new("widgets")
+            .invoke_handler(tauri::generate_handler![
+                commands::set_items,
+                commands::get_items,
+                commands::set_register_widget,
+                commands::reload_all_timelines,
+                commands::reload_timelines,
+                commands::request_widget,
+                commands::create_widget_window,
+                commands::close_widget_window,
+                commands::set_widget_config,
+                commands::get_widget_config,
+                commands::patch_widget_config,
+                commands::get_widget_config_schema,
+                commands::lint_widget_config,
+                commands::widget_action,
+                commands::poll_pending_actions,
+                commands::ack_actions,
+                commands::get_action_stats,
+                commands::open_widget_url,
+                commands::get_widget_activity,
+                commands::export_widget_activity,
+            ])
+            .setup(move |app, api| {
+                #[cfg(mobile)]
+                let widget = mobile::init(app, api, self)?;
+                #[cfg(desktop)]
+                let widget = desktop::init(app, api, self)?;
+                app.manage(widget);
+                Ok(())
+            });
+
+        #[cfg(desktop)]
+        let builder = builder.register_uri_scheme_protocol(
+            desktop::BUILTIN_PROTOCOL,
+            |ctx, _request| {
+                let html = match ctx.app_handle().try_state::<Widget<R>>() {
+                    Some(widget) => widget.renderer_html(ctx.webview_label()),
+                    None => desktop::renderer_html_default(),
+                };
+                tauri::http::Response::builder()
+                    .header("content-type", "text/html; charset=utf-8")
+                    .body(html)
+                    .unwrap()
+            },
+        );
+
+        builder.build()
+    }
+}
+
+/// Initialize the widgets plugin with default settings. Register it with
+/// `tauri::Builder::plugin()`; use [`Builder`] to customize it.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    let builder = Builder::new("widgets")
-        .invoke_handler(tauri::generate_handler![
-            commands::set_items,
-            commands::get_items,
-            commands::set_register_widget,
-            commands::reload_all_timelines,
-            commands::reload_timelines,
-            commands::request_widget,
-            commands::create_widget_window,
-            commands::close_widget_window,
-            commands::set_widget_config,
-            commands::get_widget_config,
-            commands::widget_action,
-            commands::poll_pending_actions,
-        ])
-        .setup(|app, api| {
-            #[cfg(mobile)]
-            let widget = mobile::init(app, api)?;
-            #[cfg(desktop)]
-            let widget = desktop::init(app, api)?;
-            app.manage(widget);
-            Ok(())
-        });
-
-    #[cfg(desktop)]
-    let builder = builder.register_uri_scheme_protocol(
-        desktop::BUILTIN_PROTOCOL,
-        |_app, _request| {
-            const HTML: &[u8] = include_bytes!("../widget.html");
-            tauri::http::Response::builder()
-                .header("content-type", "text/html; charset=utf-8")
-                .body(Cow::Borrowed(HTML))
-                .unwrap()
-        },
-    );
-
-    builder.build()
+    Builder::new().build()
 }
@@ -0,0 +1,317 @@
+//! De-duplication and rate limiting of widget actions.
+//!
+//! Double-clicks on desktop widgets and repeated taps on native widgets
+//! arrive as separate actions, and a misbehaving widget can send them in a
+//! loop.  Every action passes an [`ActionFilter`] before it changes widget
+//! state or reaches the app.  The filter drops:
+//!
+//! - retries: an action whose nonce was seen within the dedup window,
+//! - duplicates: an action equal to one accepted within the dedup window,
+//!   i.e. with the same group, action, element, window and payload; taps
+//!   in two widget windows of a group are two taps,
+//! - actions beyond the rate limit of their action name, counted per group.
+//!
+//! Dropped actions are counted in [`ActionStats`].
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::models::WidgetActionEvent;
+
+/// At most `max` actions every `per`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max: u32,
+    pub per: Duration,
+}
+
+/// Settings of an [`ActionFilter`], collected by the plugin builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// How long an accepted action suppresses its duplicates.  Zero turns
+    /// de-duplication off.
+    pub dedup_window: Duration,
+    /// Limits of individual action names.
+    pub rate_limits: HashMap<String, RateLimit>,
+    /// Limit of action names without their own.
+    pub default_rate_limit: Option<RateLimit>,
+}
+
+impl Default for Limits {
+    /// Duplicates within 500 ms are dropped; no rate limits.
+    fn default() -> Self {
+        Self {
+            dedup_window: Duration::from_millis(500),
+            rate_limits: HashMap::new(),
+            default_rate_limit: None,
+        }
+    }
+}
+
+impl Limits {
+    fn rate_limit(&self, action: &str) -> Option<RateLimit> {
+        self.rate_limits
+            .get(action)
+            .copied()
+            .or(self.default_rate_limit)
+    }
+}
+
+/// Counts of the actions an [`ActionFilter`] has seen since the app
+/// started.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStats {
+    pub accepted: u64,
+    /// Retries and duplicates dropped.
+    pub duplicates: u64,
+    /// Actions dropped by rate limits.
+    pub rate_limited: u64,
+    /// Dropped actions by action name.
+    pub dropped: BTreeMap<String, u64>,
+}
+
+/// What an [`ActionFilter`] decided about an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Accept,
+    Duplicate,
+    RateLimited,
+}
+
+pub struct ActionFilter {
+    limits: Limits,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Accepted actions within the dedup window, oldest first.
+    recent: VecDeque<Recent>,
+    /// Times of accepted actions within their rate limit, keyed by group
+    /// and action.
+    sent: HashMap<(String, String), VecDeque<Instant>>,
+    stats: ActionStats,
+}
+
+struct Recent {
+    at: Instant,
+    nonce: Option<String>,
+    fingerprint: u64,
+}
+
+impl ActionFilter {
+    pub fn new(limits: Limits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Decide whether to deliver `event`, counting the decision.  Accepted
+    /// events without a nonce get one.
+    pub fn check(&self, event: &mut WidgetActionEvent) -> Verdict {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let window = self.limits.dedup_window;
+        while state
+            .recent
+            .front()
+            .is_some_and(|r| now.duration_since(r.at) >= window)
+        {
+            state.recent.pop_front();
+        }
+        let fingerprint = fingerprint(event);
+        let duplicate = state
+            .recent
+            .iter()
+            .any(|r| r.fingerprint == fingerprint || (r.nonce.is_some() && r.nonce == event.nonce));
+        if duplicate {
+            return state.drop(event, Verdict::Duplicate);
+        }
+
+        if let Some(limit) = self.limits.rate_limit(&event.action) {
+            let key = (
+                event.group.clone().unwrap_or_default(),
+                event.action.clone(),
+            );
+            let sent = state.sent.entry(key).or_default();
+            while sent
+                .front()
+                .is_some_and(|at| now.duration_since(*at) >= limit.per)
+            {
+                sent.pop_front();
+            }
+            if sent.len() >= limit.max as usize {
+                return state.drop(event, Verdict::RateLimited);
+            }
+            sent.push_back(now);
+        }
+
+        let nonce = event
+            .nonce
+            .get_or_insert_with(crate::queue::next_id)
+            .clone();
+        if !window.is_zero() {
+            state.recent.push_back(Recent {
+                at: now,
+                nonce: Some(nonce),
+                fingerprint,
+            });
+        }
+        state.stats.accepted += 1;
+        Verdict::Accept
+    }
+
+    pub fn stats(&self) -> ActionStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+}
+
+impl State {
+    fn drop(&mut self, event: &WidgetActionEvent, verdict: Verdict) -> Verdict {
+        match verdict {
+            Verdict::Duplicate => self.stats.duplicates += 1,
+            Verdict::RateLimited => self.stats.rate_limited += 1,
+            Verdict::Accept => {}
+        }
+        *self.stats.dropped.entry(event.action.clone()).or_default() += 1;
+        log::debug!("dropped widget action `{}`: {verdict:?}", event.action);
+        verdict
+    }
+}
+
+/// Hash of what makes two taps the same tap.
+fn fingerprint(event: &WidgetActionEvent) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    event.group.hash(&mut hasher);
+    event.action.hash(&mut hasher);
+    event.element_id.hash(&mut hasher);
+    event.window.hash(&mut hasher);
+    event
+        .payload
+        .as_ref()
+        .map(|p| p.to_string())
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tap(group: &str, action: &str, payload: i32) -> WidgetActionEvent {
+        let mut event = WidgetActionEvent::new(action, Some(json!(payload)));
+        event.group = Some(group.into());
+        event
+    }
+
+    fn filter(dedup_window: Duration) -> ActionFilter {
+        ActionFilter::new(Limits {
+            dedup_window,
+            ..Limits::default()
+        })
+    }
+
+    #[test]
+    fn equal_actions_within_the_window_are_duplicates() {
+        let filter = filter(Duration::from_secs(60));
+        let mut first = tap("g", "a", 1);
+        assert_eq!(filter.check(&mut first), Verdict::Accept);
+        assert!(first.nonce.is_some());
+        assert_eq!(filter.check(&mut tap("g", "a", 1)), Verdict::Duplicate);
+        assert_eq!(filter.check(&mut tap("g", "a", 2)), Verdict::Accept);
+        assert_eq!(filter.check(&mut tap("h", "a", 1)), Verdict::Accept);
+
+        let mut other_window = tap("g", "a", 1);
+        other_window.window = Some("second".into());
+        assert_eq!(filter.check(&mut other_window), Verdict::Accept);
+    }
+
+    #[test]
+    fn retries_are_recognized_by_their_nonce() {
+        let filter = filter(Duration::from_secs(60));
+        let mut first = tap("g", "a", 1);
+        first.nonce = Some("n".into());
+        assert_eq!(filter.check(&mut first), Verdict::Accept);
+        let mut retry = tap("g", "b", 2);
+        retry.nonce = Some("n".into());
+        assert_eq!(filter.check(&mut retry), Verdict::Duplicate);
+    }
+
+    #[test]
+    fn duplicates_pass_once_the_window_is_over() {
+        let short = filter(Duration::from_millis(20));
+        assert_eq!(short.check(&mut tap("g", "a", 1)), Verdict::Accept);
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(short.check(&mut tap("g", "a", 1)), Verdict::Accept);
+
+        let off = filter(Duration::ZERO);
+        assert_eq!(off.check(&mut tap("g", "a", 1)), Verdict::Accept);
+        assert_eq!(off.check(&mut tap("g", "a", 1)), Verdict::Accept);
+    }
+
+    #[test]
+    fn rate_limits_count_each_group_and_action() {
+        let mut limits = Limits {
+            dedup_window: Duration::ZERO,
+            ..Limits::default()
+        };
+        let limit = |max, ms| RateLimit {
+            max,
+            per: Duration::from_millis(ms),
+        };
+        limits.rate_limits.insert("refresh".into(), limit(2, 20));
+        limits.default_rate_limit = Some(limit(1, 60_000));
+        let filter = ActionFilter::new(limits);
+
+        assert_eq!(filter.check(&mut tap("g", "refresh", 1)), Verdict::Accept);
+        assert_eq!(filter.check(&mut tap("g", "refresh", 2)), Verdict::Accept);
+        assert_eq!(
+            filter.check(&mut tap("g", "refresh", 3)),
+            Verdict::RateLimited
+        );
+        assert_eq!(filter.check(&mut tap("h", "refresh", 4)), Verdict::Accept);
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(filter.check(&mut tap("g", "refresh", 5)), Verdict::Accept);
+
+        assert_eq!(filter.check(&mut tap("g", "open", 1)), Verdict::Accept);
+        assert_eq!(filter.check(&mut tap("g", "open", 2)), Verdict::RateLimited);
+        assert_eq!(filter.check(&mut tap("g", "close", 1)), Verdict::Accept);
+    }
+
+    #[test]
+    fn stats_count_drops_by_action() {
+        let filter = ActionFilter::new(Limits {
+            rate_limits: HashMap::from([(
+                "refresh".into(),
+                RateLimit {
+                    max: 1,
+                    per: Duration::from_secs(60),
+                },
+            )]),
+            ..Limits::default()
+        });
+        filter.check(&mut tap("g", "open", 1));
+        filter.check(&mut tap("g", "open", 1));
+        filter.check(&mut tap("g", "refresh", 1));
+        filter.check(&mut tap("g", "refresh", 2));
+        assert_eq!(
+            filter.stats(),
+            ActionStats {
+                accepted: 2,
+                duplicates: 1,
+                rate_limited: 1,
+                dropped: BTreeMap::from([("open".into(), 1), ("refresh".into(), 1)]),
+            }
+        );
+    }
+}
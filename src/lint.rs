@@ -0,0 +1,144 @@
+//! Accessibility checks for widget configs.
+//!
+//! [`lint`] walks every layout of a resolved config and reports elements
+//! that screen readers cannot announce usefully: images without an
+//! `accessibilityLabel` (VoiceOver reads the SF Symbol name, TalkBack and
+//! desktop readers nothing) and buttons or links that only show an icon.
+//! Issues are advisory; configs with issues are still stored.
+
+use serde::Serialize;
+
+use crate::models::{WidgetConfig, WidgetElement, WidgetFamily};
+
+/// An accessibility problem found by [`lint`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintIssue {
+    /// Name of the rule: `image-label`, `icon-button-label` or
+    /// `hidden-interactive`.
+    pub rule: &'static str,
+    /// JSON Pointer of the element, e.g. `/small/children/0`.
+    pub path: String,
+    pub message: String,
+}
+
+/// Check every layout of `config`.  Element paths refer to the resolved
+/// config, so styles, components and localized strings are already applied.
+pub fn lint(config: &WidgetConfig) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    for family in WidgetFamily::ALL {
+        if let Some(root) = config.own_layout(family) {
+            check(root, &format!("/{}", family.key()), false, &mut issues);
+        }
+    }
+    issues
+}
+
+/// `labelled` is set below elements whose label already describes their
+/// content, such as a labelled link around an icon.
+fn check(element: &WidgetElement, path: &str, labelled: bool, issues: &mut Vec<LintIssue>) {
+    let Some(style) = element.style() else {
+        return;
+    };
+    let interactive = matches!(
+        element,
+        WidgetElement::Button { .. }
+            | WidgetElement::Link { .. }
+            | WidgetElement::Toggle { .. }
+            | WidgetElement::Segmented { .. }
+    );
+    if style.accessibility_hidden == Some(true) {
+        if interactive {
+            issue(
+                issues,
+                "hidden-interactive",
+                path,
+                "tappable element is hidden from screen readers, so their users cannot activate it",
+            );
+        }
+        return;
+    }
+    let labelled = labelled || style.accessibility_label.is_some();
+
+    match element {
+        WidgetElement::Image { system_name, .. } if !labelled => {
+            let shown = match system_name {
+                Some(name) => format!("image `{name}`"),
+                None => "image".to_string(),
+            };
+            issue(
+                issues,
+                "image-label",
+                path,
+                &format!(
+                    "{shown} has no accessibilityLabel; set one, or accessibilityHidden if it is decorative"
+                ),
+            );
+        }
+        WidgetElement::Canvas { .. } if !labelled => issue(
+            issues,
+            "image-label",
+            path,
+            "canvas has no accessibilityLabel; set one, or accessibilityHidden if it is decorative",
+        ),
+        WidgetElement::Button { label, .. } if !labelled && !is_readable(label) => issue(
+            issues,
+            "icon-button-label",
+            path,
+            &format!("button `{label}` shows no readable text; set an accessibilityLabel"),
+        ),
+        WidgetElement::Link { children, .. } if !labelled && !children.iter().any(has_text) => {
+            issue(
+                issues,
+                "icon-button-label",
+                path,
+                "link contains no text; set an accessibilityLabel",
+            );
+            // Reported once on the link rather than on every icon inside.
+            for (i, child) in children.iter().enumerate() {
+                check(child, &format!("{path}/children/{i}"), true, issues);
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    for (i, child) in element.children().iter().enumerate() {
+        check(child, &format!("{path}/children/{i}"), labelled, issues);
+    }
+}
+
+/// Whether `element` or one of its descendants shows readable text.
+fn has_text(element: &WidgetElement) -> bool {
+    if element.style().and_then(|s| s.accessibility_hidden) == Some(true) {
+        return false;
+    }
+    if element
+        .style()
+        .is_some_and(|s| s.accessibility_label.is_some())
+    {
+        return true;
+    }
+    match element {
+        WidgetElement::Text { content, .. } => is_readable(content),
+        WidgetElement::RichText { spans, .. } => spans.iter().any(|s| is_readable(&s.text)),
+        WidgetElement::Label { text, .. } => is_readable(text),
+        WidgetElement::Stat { value, .. } => is_readable(value),
+        WidgetElement::Button { label, .. } => is_readable(label),
+        WidgetElement::Date { .. } | WidgetElement::Timer { .. } => true,
+        _ => element.children().iter().any(has_text),
+    }
+}
+
+/// Text made only of symbols or emoji (`"✕"`, `"⟳"`) is not readable.
+fn is_readable(text: &str) -> bool {
+    text.chars().any(char::is_alphanumeric)
+}
+
+fn issue(issues: &mut Vec<LintIssue>, rule: &'static str, path: &str, message: &str) {
+    issues.push(LintIssue {
+        rule,
+        path: path.to_string(),
+        message: message.to_string(),
+    });
+}
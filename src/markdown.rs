@@ -0,0 +1,183 @@
+//! Lightweight markdown for `richText` elements.
+//!
+//! Supports the inline subset that fits in a widget: `**bold**` /
+//! `__bold__`, `*italic*` / `_italic_`, `~~strikethrough~~`, `` `code` ``
+//! and `[label](url)`.  A backslash escapes the next punctuation character.
+//! Markers without a closing counterpart are kept as literal text, and `_`
+//! inside a word (`snake_case`) never starts emphasis.  Block syntax
+//! (headings, lists, paragraphs) is not interpreted.
+
+use crate::models::{FontWeight, TextSpan};
+
+/// Parse `source` into spans.  Adjacent runs with the same styling are
+/// merged, so plain text yields a single span.
+pub fn parse(source: &str) -> Vec<TextSpan> {
+    let mut parser = Parser::default();
+    parser.run(source);
+    parser.flush();
+    parser.spans
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Marks {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mark {
+    Bold,
+    Italic,
+    Strike,
+}
+
+/// Emphasis markers, longest first so `**` wins over `*`.
+const MARKERS: [(&str, Mark); 5] = [
+    ("**", Mark::Bold),
+    ("__", Mark::Bold),
+    ("~~", Mark::Strike),
+    ("*", Mark::Italic),
+    ("_", Mark::Italic),
+];
+
+#[derive(Default)]
+struct Parser {
+    spans: Vec<TextSpan>,
+    /// Styling of the last pushed span, for merging.
+    last: Option<(Marks, Option<String>)>,
+    text: String,
+    marks: Marks,
+    link: Option<String>,
+}
+
+impl Parser {
+    fn run(&mut self, src: &str) {
+        let mut i = 0;
+        while i < src.len() {
+            let rest = &src[i..];
+            let prev = src[..i].chars().next_back();
+
+            if let Some(c) = rest
+                .strip_prefix('\\')
+                .and_then(|r| r.chars().next())
+                .filter(char::is_ascii_punctuation)
+            {
+                self.text.push(c);
+                i += 1 + c.len_utf8();
+                continue;
+            }
+
+            if let Some(end) = rest.strip_prefix('`').and_then(|r| r.find('`')) {
+                let saved = self.marks;
+                self.flush();
+                self.marks.code = true;
+                self.text.push_str(&rest[1..1 + end]);
+                self.flush();
+                self.marks = saved;
+                i += end + 2;
+                continue;
+            }
+
+            if self.link.is_none() {
+                if let Some((label, url, len)) = link(rest) {
+                    let saved = self.marks;
+                    self.flush();
+                    self.link = Some(url.to_string());
+                    self.run(label);
+                    self.flush();
+                    self.link = None;
+                    self.marks = saved;
+                    i += len;
+                    continue;
+                }
+            }
+
+            if let Some((marker, mark)) = MARKERS.iter().find(|(m, _)| rest.starts_with(m)) {
+                let after = &rest[marker.len()..];
+                if !self.toggle(*mark, marker, prev, after) {
+                    self.text.push_str(marker);
+                }
+                i += marker.len();
+                continue;
+            }
+
+            let c = rest.chars().next().unwrap_or_default();
+            self.text.push(c);
+            i += c.len_utf8();
+        }
+    }
+
+    /// Open or close `mark`.  Returns `false` when the marker is literal.
+    fn toggle(&mut self, mark: Mark, marker: &str, prev: Option<char>, after: &str) -> bool {
+        let next = after.chars().next();
+        let intraword = marker.starts_with('_')
+            && prev.is_some_and(char::is_alphanumeric)
+            && next.is_some_and(char::is_alphanumeric);
+        if intraword {
+            return false;
+        }
+        let open = match mark {
+            Mark::Bold => self.marks.bold,
+            Mark::Italic => self.marks.italic,
+            Mark::Strike => self.marks.strike,
+        };
+        // Openers must precede text and have a closer; closers must follow text.
+        let valid = if open {
+            prev.is_some_and(|c| !c.is_whitespace())
+        } else {
+            next.is_some_and(|c| !c.is_whitespace()) && after.contains(marker)
+        };
+        if !valid {
+            return false;
+        }
+        self.flush();
+        let flag = match mark {
+            Mark::Bold => &mut self.marks.bold,
+            Mark::Italic => &mut self.marks.italic,
+            Mark::Strike => &mut self.marks.strike,
+        };
+        *flag = !open;
+        true
+    }
+
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+        let text = std::mem::take(&mut self.text);
+        let key = (self.marks, self.link.clone());
+        if self.last.as_ref() == Some(&key) {
+            if let Some(span) = self.spans.last_mut() {
+                span.text.push_str(&text);
+                return;
+            }
+        }
+        let on = |flag: bool| flag.then_some(true);
+        self.spans.push(TextSpan {
+            text,
+            font_weight: self.marks.bold.then_some(FontWeight::Bold),
+            italic: on(self.marks.italic),
+            strikethrough: on(self.marks.strike),
+            monospace: on(self.marks.code),
+            link: self.link.clone(),
+            ..TextSpan::default()
+        });
+        self.last = Some(key);
+    }
+}
+
+/// Split `[label](url)` at the start of `s` into label, url and length.
+fn link(s: &str) -> Option<(&str, &str, usize)> {
+    let body = s.strip_prefix('[')?;
+    let close = body.find("](")?;
+    let label = &body[..close];
+    let url_start = close + 2;
+    let url_len = body[url_start..].find(')')?;
+    let url = &body[url_start..url_start + url_len];
+    if label.is_empty() || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, url, 1 + url_start + url_len + 1))
+}
@@ -0,0 +1,236 @@
+//! Versioned upgrades for stored widget configs.
+//!
+//! Every stored config carries a `version` (missing means `1`).  When a
+//! config is read back it is upgraded step by step — `1 → 2 → …` — until it
+//! reaches the newest version known to this build, then deserialized.
+//! Configs the app stores are stamped with that version, as they are
+//! written in the shape of the current build.
+//! Apps register their own steps with [`Builder::migration`](crate::Builder::migration)
+//! when they change the shape of their configs.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::Error;
+
+/// Config version understood by the built-in models.
+pub const CURRENT_VERSION: u32 = 1;
+
+/// A single upgrade step.  Receives the config JSON at version `from` and
+/// rewrites it in place to version `from + 1`; the `version` field is
+/// updated by the caller.
+pub type Migration = Box<dyn Fn(&mut Value) -> crate::Result<()> + Send + Sync>;
+
+/// Ordered set of upgrade steps keyed by the version they upgrade from.
+#[derive(Default)]
+pub struct Migrations {
+    steps: BTreeMap<u32, Migration>,
+}
+
+impl fmt::Debug for Migrations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Migrations")
+            .field("from", &self.steps.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Migrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the step that upgrades configs from version `from` to
+    /// `from + 1`.  Replaces any step previously registered for `from`.
+    pub fn register<F>(&mut self, from: u32, migration: F)
+    where
+        F: Fn(&mut Value) -> crate::Result<()> + Send + Sync + 'static,
+    {
+        self.steps.insert(from, Box::new(migration));
+    }
+
+    /// The newest config version this build can read: the built-in
+    /// [`CURRENT_VERSION`] or the target of the last registered step.
+    pub fn target_version(&self) -> u32 {
+        self.steps
+            .keys()
+            .next_back()
+            .map_or(CURRENT_VERSION, |from| (from + 1).max(CURRENT_VERSION))
+    }
+
+    /// Parse a stored config and upgrade it.  Returns `true` along with the
+    /// config when it was upgraded and should be saved again.
+    pub fn load(&self, raw: &str) -> crate::Result<(Value, bool)> {
+        let mut doc: Value = serde_json::from_str(raw)
+            .map_err(|e| Error::SerdeJson(format!("parse config: {e}")))?;
+        let migrated = self.migrate(&mut doc)?;
+        Ok((doc, migrated))
+    }
+
+    /// Mark `doc`, a config the app is storing, as written for this build:
+    /// its `version` becomes [`target_version`](Self::target_version), so
+    /// it is not migrated when read back.  Fails for configs newer than
+    /// this build.
+    pub fn stamp(&self, doc: &mut Value) -> crate::Result<()> {
+        let target = self.target_version();
+        let version = config_version(doc)?;
+        if version > target {
+            return Err(too_new(version, target));
+        }
+        if let Some(obj) = doc.as_object_mut() {
+            obj.insert("version".into(), Value::from(target));
+        }
+        Ok(())
+    }
+
+    /// Upgrade `doc` to [`target_version`](Self::target_version).
+    ///
+    /// Returns `true` when `doc` was changed.  Fails without touching `doc`
+    /// when the config is newer than this build or a step is missing.
+    pub fn migrate(&self, doc: &mut Value) -> crate::Result<bool> {
+        let target = self.target_version();
+        let version = config_version(doc)?;
+        if version > target {
+            return Err(too_new(version, target));
+        }
+        if version == target {
+            return Ok(false);
+        }
+
+        let mut work = doc.clone();
+        for from in version..target {
+            let step = self.steps.get(&from).ok_or_else(|| {
+                Error::invalid(
+                    "/version",
+                    format!(
+                        "no migration registered from widget config version {from} to {}",
+                        from + 1
+                    ),
+                )
+            })?;
+            step(&mut work).map_err(|e| {
+                Error::invalid(
+                    "",
+                    format!("widget config migration {from} -> {} failed: {e}", from + 1),
+                )
+            })?;
+            let obj = work.as_object_mut().ok_or_else(|| {
+                Error::invalid(
+                    "",
+                    format!("widget config migration from {from} did not return an object"),
+                )
+            })?;
+            obj.insert("version".into(), Value::from(from + 1));
+        }
+        *doc = work;
+        Ok(true)
+    }
+}
+
+fn too_new(version: u32, target: u32) -> Error {
+    Error::invalid(
+        "/version",
+        format!(
+            "widget config version {version} is newer than the supported version {target}; \
+             update the app to read it"
+        ),
+    )
+}
+
+/// Read the `version` field of a raw config, defaulting to `1`.
+fn config_version(doc: &Value) -> crate::Result<u32> {
+    let obj = doc
+        .as_object()
+        .ok_or_else(|| Error::invalid("", "widget config must be a JSON object"))?;
+    match obj.get("version") {
+        None | Some(Value::Null) => Ok(1),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| {
+                Error::invalid("/version", format!("invalid widget config version `{v}`"))
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn code(result: crate::Result<(Value, bool)>) -> Value {
+        let error = result.expect_err("load should fail");
+        serde_json::to_value(error).unwrap()["code"].clone()
+    }
+
+    #[test]
+    fn unparsable_configs_are_serde_errors() {
+        assert_eq!(code(Migrations::new().load("{ not json")), "serde");
+    }
+
+    #[test]
+    fn newer_configs_are_validation_errors() {
+        assert_eq!(
+            code(Migrations::new().load(r#"{"version":9}"#)),
+            "validation"
+        );
+    }
+
+    #[test]
+    fn failed_steps_are_validation_errors() {
+        let mut migrations = Migrations::new();
+        migrations.register(1, |_| Err(Error::new("boom")));
+        assert_eq!(code(migrations.load(r#"{"version":1}"#)), "validation");
+
+        let mut migrations = Migrations::new();
+        migrations.register(1, |doc| {
+            *doc = json!([]);
+            Ok(())
+        });
+        assert_eq!(code(migrations.load("{}")), "validation");
+    }
+
+    #[test]
+    fn missing_steps_are_validation_errors() {
+        let mut migrations = Migrations::new();
+        migrations.register(2, |_| Ok(()));
+        assert_eq!(code(migrations.load("{}")), "validation");
+    }
+
+    #[test]
+    fn configs_set_at_the_old_version_are_not_migrated_again() {
+        let mut migrations = Migrations::new();
+        migrations.register(1, |doc| {
+            doc["renamed"] = json!(true);
+            Ok(())
+        });
+        let mut doc = json!({ "version": 1, "small": { "type": "text" } });
+        migrations.stamp(&mut doc).unwrap();
+        assert_eq!(doc["version"], 2);
+        let (read, migrated) = migrations.load(&doc.to_string()).unwrap();
+        assert!(!migrated);
+        assert_eq!(read, doc);
+    }
+
+    #[test]
+    fn newer_configs_cannot_be_stored() {
+        let mut doc = json!({ "version": 3 });
+        let error = Migrations::new().stamp(&mut doc).unwrap_err();
+        assert_eq!(error.code(), "validation");
+    }
+
+    #[test]
+    fn older_configs_are_upgraded() {
+        let mut migrations = Migrations::new();
+        migrations.register(1, |doc| {
+            doc["renamed"] = json!(true);
+            Ok(())
+        });
+        let (doc, migrated) = migrations.load("{}").unwrap();
+        assert!(migrated);
+        assert_eq!(doc, json!({ "renamed": true, "version": 2 }));
+    }
+}
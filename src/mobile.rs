@@ -2,13 +2,21 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::env;
+use std::fs;
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 use std::sync::Mutex;
 use std::time::Instant;
-use tauri::{plugin::PluginApi, AppHandle, Runtime};
+use tauri::{plugin::PluginApi, AppHandle, Manager, Runtime};
 
-use crate::models::{WidgetConfig, WidgetWindowConfig};
+use crate::actions::{ControlKind, Controls};
+use crate::activity::{ActivityEntry, ActivityLog, ActivityQuery};
+use crate::handlers::{ActionContext, ActionDispatcher, QueueStore};
+use crate::limits::{ActionFilter, ActionStats};
+use crate::migrations::Migrations;
+use crate::models::{ConfigPatch, WidgetActionEvent, WidgetConfig, WidgetWindowConfig};
 
 /// Default minimum interval between WidgetKit reload calls.
 /// Can be overridden with `TAURI_WIDGET_MIN_RELOAD_SECS`.
@@ -34,17 +42,34 @@ const PLUGIN_IDENTIFIER: &str = "git.s00d.widgets";
 tauri::ios_plugin_binding!(init_plugin_widgets);
 
 pub fn init<R: Runtime, C: DeserializeOwned>(
-    _app: &AppHandle<R>,
+    app: &AppHandle<R>,
     api: PluginApi<R, C>,
+    options: crate::Builder,
 ) -> crate::Result<Widget<R>> {
     #[cfg(target_os = "android")]
     let handle = api.register_android_plugin(PLUGIN_IDENTIFIER, "WidgetBridgePlugin")?;
     #[cfg(target_os = "ios")]
     let handle = api.register_ios_plugin(init_plugin_widgets)?;
+    let activity_file = app
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join("widgets").join("activity.jsonl"));
     Ok(Widget {
+        app: app.clone(),
         handle,
         last_config_hash: Mutex::new(0),
         last_reload: Mutex::new(None),
+        controls: Mutex::new(HashMap::new()),
+        migrations: options.migrations,
+        locale: options.locale,
+        actions: ActionDispatcher::new(
+            app.clone(),
+            ActionFilter::new(options.action_limits),
+            ActivityLog::new(options.activity, activity_file),
+            options.action_retention,
+            options.manual_action_ack,
+        ),
     })
 }
 
@@ -86,9 +111,19 @@ struct GroupPayload<'a> {
 // ── Widget ──────────────────────────────────────────────────────────────────
 
 pub struct Widget<R: Runtime> {
+    app: AppHandle<R>,
     handle: tauri::plugin::PluginHandle<R>,
     last_config_hash: Mutex<u64>,
     last_reload: Mutex<Option<Instant>>,
+    /// Segmented controls and bound toggles of each group's config, so
+    /// that taps on other elements do not read the stored config.
+    controls: Mutex<HashMap<String, Controls>>,
+    /// Upgrade steps applied to stored configs when they are read.
+    migrations: Migrations,
+    /// Locale set on the plugin builder, overriding the system locale.
+    locale: Option<String>,
+    /// Handlers, limits, activity log and queue of widget actions.
+    actions: ActionDispatcher<R>,
 }
 
 impl<R: Runtime> Widget<R> {
@@ -117,10 +152,12 @@ impl<R: Runtime> Widget<R> {
     }
 
     pub fn reload_all_timelines(&self) -> crate::Result<bool> {
-        self.handle
+        let result = self
+            .handle
             .run_mobile_plugin("reloadAllTimelines", ())
             .map(|_: Value| true)
-            .map_err(Into::into)
+            .map_err(Into::into);
+        self.actions.activity.reload("all", result)
     }
 
     /// Rate-limited reload: skips the actual WidgetKit call if the last
@@ -145,10 +182,12 @@ impl<R: Runtime> Widget<R> {
     }
 
     pub fn reload_timelines(&self, of_kind: &str) -> crate::Result<bool> {
-        self.handle
+        let result = self
+            .handle
             .run_mobile_plugin("reloadTimelines", ReloadPayload { of_kind })
             .map(|_: Value| true)
-            .map_err(Into::into)
+            .map_err(Into::into);
+        self.actions.activity.reload(of_kind, result)
     }
 
     pub fn request_widget(&self) -> crate::Result<bool> {
@@ -170,12 +209,59 @@ impl<R: Runtime> Widget<R> {
         ))
     }
 
-    pub fn set_widget_config(&self, config: &WidgetConfig, group: &str, skip_reload: bool) -> crate::Result<bool> {
-        let json = serde_json::to_string(config)
-            .map_err(|e| crate::Error::new(format!("serialize config: {e}")))?;
+    pub(crate) fn open_url(
+        &self,
+        _url: &str,
+        _group: Option<String>,
+        _window: Option<String>,
+        _element_id: Option<String>,
+    ) -> crate::Result<()> {
+        Err(crate::Error::Unsupported(
+            "Webview widgets are desktop only".into(),
+        ))
+    }
+
+    /// There are no widget windows on mobile; always `None`.
+    pub fn window_group(&self, _label: &str) -> Option<String> {
+        None
+    }
+
+    /// Store `config` for `group` and reload native widgets.
+    ///
+    /// `config` is a [`WidgetConfig`] or its raw JSON form; named styles and
+    /// design tokens are resolved and the result validated before storing.
+    pub fn set_widget_config(
+        &self,
+        config: &impl Serialize,
+        group: &str,
+        skip_reload: bool,
+    ) -> crate::Result<bool> {
+        let source = serde_json::to_value(config)
+            .map_err(|e| crate::Error::SerdeJson(format!("serialize config: {e}")))?;
+        self.store_widget_config(source, group, skip_reload).map(|_| true)
+    }
+
+    fn store_widget_config(
+        &self,
+        mut source: Value,
+        group: &str,
+        skip_reload: bool,
+    ) -> crate::Result<WidgetConfig> {
+        // Set by the app as of this build, so it must not be migrated when
+        // read back.
+        self.migrations.stamp(&mut source)?;
+        let toggles = crate::actions::sync_toggles(&source, |key| self.toggle_state(key, group));
+        crate::patch::apply(&mut source, &toggles)?;
+        let (config, mut resolved) = crate::resolve::resolve(&source, &self.locale())?;
+        crate::fallback::lower(&mut resolved);
+        let json = serde_json::to_string(&resolved)
+            .map_err(|e| crate::Error::SerdeJson(format!("serialize config: {e}")))?;
+        let source_json = serde_json::to_string(&source)
+            .map_err(|e| crate::Error::SerdeJson(format!("serialize config: {e}")))?;
 
         let mut hasher = DefaultHasher::new();
         json.hash(&mut hasher);
+        source_json.hash(&mut hasher);
         let new_hash = hasher.finish();
 
         let changed = {
@@ -189,33 +275,308 @@ impl<R: Runtime> Widget<R> {
         };
 
         if changed {
+            self.set_items("__widget_config_source__", &source_json, group)?;
             self.set_items("__widget_config__", &json, group)?;
             if !skip_reload {
                 self.throttled_reload()?;
             }
         }
-        Ok(true)
+        self.controls
+            .lock()
+            .unwrap()
+            .insert(group.to_string(), Controls::of(&source));
+        Ok(config)
     }
 
+    /// The resolved config of `group`, as rendered by widgets.
     pub fn get_widget_config(&self, group: &str) -> crate::Result<Option<WidgetConfig>> {
-        match self.get_items("__widget_config__", group)? {
-            Some(json) => {
-                let config: WidgetConfig = serde_json::from_str(&json)
-                    .map_err(|e| crate::Error::new(format!("parse config: {e}")))?;
-                Ok(Some(config))
+        let Some(doc) = self.stored_config("__widget_config__", group)? else {
+            return Ok(None);
+        };
+        let config: WidgetConfig = serde_json::from_value(doc)
+            .map_err(|e| crate::Error::SerdeJson(format!("parse config: {e}")))?;
+        Ok(Some(config))
+    }
+
+    /// The config of `group` with strings resolved for `locale` rather than
+    /// the current locale.  Same as [`get_widget_config`](Self::get_widget_config)
+    /// for configs without localized strings.
+    pub fn get_localized_widget_config(
+        &self,
+        group: &str,
+        locale: &str,
+    ) -> crate::Result<Option<WidgetConfig>> {
+        match self.stored_config("__widget_config_source__", group)? {
+            Some(source) if source.get("strings").is_some() => {
+                crate::resolve::resolve(&source, locale).map(|(config, _)| Some(config))
+            }
+            _ => self.get_widget_config(group),
+        }
+    }
+
+    /// Locale used to resolve localized strings: the one set on the plugin
+    /// builder, or the system locale.
+    pub fn locale(&self) -> String {
+        self.locale.clone().unwrap_or_else(crate::i18n::system_locale)
+    }
+
+    /// Read a stored config JSON of `group`, upgrading and re-saving it
+    /// when it was written with an older version.
+    fn stored_config(&self, key: &str, group: &str) -> crate::Result<Option<Value>> {
+        let Some(raw) = self.get_items(key, group)? else {
+            return Ok(None);
+        };
+        let (doc, migrated) = self.migrations.load(&raw)?;
+        if migrated {
+            self.set_items(key, &doc.to_string(), group)?;
+        }
+        Ok(Some(doc))
+    }
+
+    /// Apply `patches` to the config of `group` as it was set (before
+    /// styles and tokens were resolved), then store it like
+    /// [`set_widget_config`](Self::set_widget_config).
+    pub fn patch_widget_config(
+        &self,
+        group: &str,
+        patches: &[ConfigPatch],
+        skip_reload: bool,
+    ) -> crate::Result<WidgetConfig> {
+        let mut doc = match self.stored_config("__widget_config_source__", group)? {
+            Some(doc) => doc,
+            None => self.stored_config("__widget_config__", group)?.ok_or_else(|| {
+                crate::Error::not_found(format!("no widget config stored for group `{group}`"))
+            })?,
+        };
+        crate::patch::apply(&mut doc, patches)?;
+        self.store_widget_config(doc, group, skip_reload)
+    }
+
+    /// Move the selection of the segmented control or flip the bound toggle
+    /// that `event` tapped, before the app hears about it.  Taps on other
+    /// elements leave the config alone.  `skip_reload` is for taps on native
+    /// widgets, which already show the new state.
+    pub(crate) fn apply_tap(
+        &self,
+        group: &str,
+        event: &mut WidgetActionEvent,
+        skip_reload: bool,
+    ) -> crate::Result<()> {
+        match self.tapped_control(group, &event.action, event.element_id.as_deref())? {
+            Some(ControlKind::Selector) => {
+                if let Some(value) = event.payload.as_ref().and_then(Value::as_str) {
+                    self.select_option(group, &event.action, value, skip_reload)?;
+                }
+            }
+            Some(ControlKind::Toggle) => {
+                let state = event
+                    .payload
+                    .as_ref()
+                    .and_then(crate::actions::payload_state);
+                if let Some(is_on) = self.set_toggle(group, &event.action, state, skip_reload)? {
+                    event.payload = Some(is_on.to_string().into());
+                }
             }
-            None => Ok(None),
+            None => {}
         }
+        Ok(())
     }
 
-    pub fn poll_pending_actions(&self, group: &str) -> crate::Result<Vec<Value>> {
+    /// Kind of the control of `group` that a tap emitting `action` hit.
+    fn tapped_control(
+        &self,
+        group: &str,
+        action: &str,
+        element_id: Option<&str>,
+    ) -> crate::Result<Option<ControlKind>> {
+        if let Some(controls) = self.controls.lock().unwrap().get(group) {
+            return Ok(controls.find(action, element_id));
+        }
+        let controls = self
+            .stored_config("__widget_config_source__", group)?
+            .map(|doc| Controls::of(&doc))
+            .unwrap_or_default();
+        let kind = controls.find(action, element_id);
+        self.controls
+            .lock()
+            .unwrap()
+            .insert(group.to_string(), controls);
+        Ok(kind)
+    }
+
+    /// Select the option with `value` in the segmented controls of `group`
+    /// that emit `action`, as if the app had patched the config with
+    /// `skip_reload`.  Returns `false` when no control changed.
+    pub fn select_option(
+        &self,
+        group: &str,
+        action: &str,
+        value: &str,
+        skip_reload: bool,
+    ) -> crate::Result<bool> {
+        let Some(doc) = self.stored_config("__widget_config_source__", group)? else {
+            return Ok(false);
+        };
+        let patches = crate::actions::select_option(&doc, action, value);
+        if patches.is_empty() {
+            return Ok(false);
+        }
+        self.patch_widget_config(group, &patches, skip_reload)
+            .map(|_| true)
+    }
+
+    /// Set the toggle of `group` that emits `action` and is bound to a
+    /// store key to `is_on`, or flip it when `None`, as if the app had
+    /// patched the config with `skip_reload`.  Returns the new state, or
+    /// `None` when no bound toggle emits `action`.
+    pub fn set_toggle(
+        &self,
+        group: &str,
+        action: &str,
+        is_on: Option<bool>,
+        skip_reload: bool,
+    ) -> crate::Result<Option<bool>> {
+        let Some(doc) = self.stored_config("__widget_config_source__", group)? else {
+            return Ok(None);
+        };
+        let current = |key: &str| self.toggle_state(key, group);
+        let Some(change) = crate::actions::set_toggle(&doc, action, is_on, current) else {
+            return Ok(None);
+        };
+        self.set_items(&change.key, &change.is_on.to_string(), group)?;
+        if !change.patches.is_empty() {
+            self.patch_widget_config(group, &change.patches, skip_reload)?;
+        }
+        Ok(Some(change.is_on))
+    }
+
+    /// State of a toggle store key.
+    fn toggle_state(&self, key: &str, group: &str) -> Option<bool> {
+        let value = self.get_items(key, group).ok().flatten()?;
+        crate::actions::parse_state(&value)
+    }
+
+    /// Take the actions queued by the native widget and return them with
+    /// the actions not acknowledged yet, oldest first.  Actions with a Rust
+    /// handler are handled here, acknowledged and left out of the result;
+    /// if the handler fails they stay queued for the next poll, until they
+    /// have failed as often as the builder's retry limit.  Without manual
+    /// acknowledgement, the returned actions count as delivered and are
+    /// acknowledged too.
+    pub fn poll_pending_actions(&self, group: &str) -> crate::Result<Vec<WidgetActionEvent>> {
         let res: Value = self
             .handle
             .run_mobile_plugin("pollPendingActions", GroupPayload { group })?;
-        Ok(res
+        let pending = res
             .get("results")
             .and_then(|v| v.as_array())
             .cloned()
-            .unwrap_or_default())
+            .unwrap_or_default();
+        let mut incoming = Vec::new();
+        for mut event in pending.iter().filter_map(crate::actions::pending_event) {
+            event.group.get_or_insert_with(|| group.to_string());
+            if matches!(self.admit_action(&mut event), Ok(true)) {
+                incoming.push(event);
+            }
+        }
+        // The native queue is cleared now; the actions live on in ours.
+        self.actions.poll(self, group, incoming)
+    }
+
+    /// Remove the actions with the given ids from the queue of `group`.
+    /// Returns how many were removed.
+    pub fn ack_actions(&self, group: &str, ids: &[String]) -> crate::Result<usize> {
+        self.actions.ack(self, group, ids)
+    }
+
+    /// Run the handler of `event` or emit it as the `widget-action` event.
+    /// The action is queued first and stays queued until acknowledged or
+    /// polled, unless a handler handles it.  Actions without a group cannot
+    /// be queued.
+    pub(crate) fn deliver_action(&self, event: WidgetActionEvent) -> crate::Result<()> {
+        self.actions.deliver(self, event)
+    }
+
+    /// Pass `event` through the de-duplication and rate limits set on the
+    /// builder.  Returns `false` for a duplicate and a
+    /// [`Throttled`](crate::Error::Throttled) error when a rate limit drops it.
+    pub(crate) fn admit_action(&self, event: &mut WidgetActionEvent) -> crate::Result<bool> {
+        self.actions.admit(event)
+    }
+
+    /// Counts of accepted and dropped actions since the app started.
+    pub fn action_stats(&self) -> ActionStats {
+        self.actions.filter.stats()
+    }
+
+    /// Entries of the activity log matching `query`, oldest first.
+    pub fn widget_activity(&self, query: &ActivityQuery) -> Vec<ActivityEntry> {
+        self.actions.activity.entries(query)
+    }
+
+    /// Entries of the activity log matching `query` as JSON lines.
+    pub fn export_widget_activity(&self, query: &ActivityQuery) -> String {
+        self.actions.activity.export(query)
+    }
+
+    // ── Action handlers ─────────────────────────────────────────────────────
+
+    /// Run `handler` when a widget emits `action`, instead of emitting the
+    /// `widget-action` event.  The payload is deserialized into `T` as
+    /// described in [`parse_payload`](crate::handlers::parse_payload); a
+    /// payload that does not fit is reported as an error of the action.
+    /// Replaces any handler previously registered for `action`.
+    pub fn on_action<T, F>(&self, action: &str, handler: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(&ActionContext<R>, T) -> crate::Result<()> + Send + Sync + 'static,
+    {
+        self.actions.handlers.register(action, handler);
+    }
+
+    /// Remove the handler registered for `action`, so the action is emitted
+    /// as the `widget-action` event again.
+    pub fn remove_action_handler(&self, action: &str) -> bool {
+        self.actions.handlers.remove(action)
+    }
+
+    /// File holding the action queue of `group`.  Kept in the app's data
+    /// directory rather than the store shared with the native widgets.
+    fn queue_path(&self, group: &str) -> crate::Result<PathBuf> {
+        let dir = self
+            .app
+            .path()
+            .app_data_dir()
+            .map_err(|e| crate::Error::Io(e.to_string()))?;
+        let name: String = group
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+        Ok(dir
+            .join("widgets")
+            .join("actions")
+            .join(format!("{name}.json")))
+    }
+}
+
+impl<R: Runtime> QueueStore for Widget<R> {
+    fn read_queue(&self, group: &str) -> crate::Result<Option<String>> {
+        match fs::read_to_string(self.queue_path(group)?) {
+            Ok(json) => Ok(Some(json)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write_queue(&self, group: &str, json: &str) -> crate::Result<()> {
+        let path = self.queue_path(group)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, json)?;
+        Ok(())
     }
 }
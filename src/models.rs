@@ -1,7 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// A single key-value item belonging to a widget group.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WidgetItem {
     pub key: String,
@@ -15,7 +17,7 @@ pub struct WidgetItem {
 /// automatically (via a custom URI-scheme protocol).  In that case
 /// `group` tells the renderer which config to load, and `size`
 /// selects the layout family (`"small"`, `"medium"`, or `"large"`).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WidgetWindowConfig {
     pub label: String,
@@ -23,7 +25,13 @@ pub struct WidgetWindowConfig {
     /// widget renderer that ships with the plugin.
     #[serde(default)]
     pub url: Option<String>,
+    /// Window width in logical pixels.  `0`, the default when omitted,
+    /// uses the typical width of the `size` family.
+    #[serde(default)]
     pub width: f64,
+    /// Window height in logical pixels.  `0`, the default when omitted,
+    /// uses the typical height of the `size` family.
+    #[serde(default)]
     pub height: f64,
     pub x: Option<f64>,
     pub y: Option<f64>,
@@ -35,10 +43,72 @@ pub struct WidgetWindowConfig {
     /// knows which config to load via `get_widget_config`.
     #[serde(default)]
     pub group: Option<String>,
-    /// Size family the renderer should display: `"small"`, `"medium"`,
-    /// or `"large"`.  Defaults to `"small"` when omitted.
+    /// Size family the renderer should display.  Defaults to `"small"`
+    /// when omitted; missing layouts fall back to related families.
+    #[serde(default)]
+    pub size: Option<WidgetFamily>,
+    /// Theme for the built-in renderer.  Overrides the plugin-wide theme
+    /// set with `Builder::theme`.  Ignored when a custom `url` is provided.
+    #[serde(default)]
+    pub theme: Option<WidgetTheme>,
+}
+
+impl WidgetWindowConfig {
+    /// Size family of the window, `small` unless set.
+    pub fn family(&self) -> WidgetFamily {
+        self.size.unwrap_or(WidgetFamily::Small)
+    }
+
+    /// Window size, with the typical size of the family for an unset
+    /// `width` or `height`.
+    pub fn inner_size(&self) -> (f64, f64) {
+        let (width, height) = self.family().default_size();
+        let or = |value: f64, default: f64| if value > 0.0 { value } else { default };
+        (or(self.width, width), or(self.height, height))
+    }
+}
+
+/// Look and feel of the built-in desktop renderer.
+///
+/// The theme is turned into a `<style>` block that the plugin's protocol
+/// handler injects into `widget.html`, so apps can brand their widgets
+/// without shipping a renderer of their own.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetTheme {
+    /// CSS custom properties set on `:root`, e.g.
+    /// `{ "widget-font-family": "Inter, sans-serif" }`.  The leading `--`
+    /// is optional.  See `widget.html` for the variables the renderer reads.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// Extra CSS appended after the built-in styles.
     #[serde(default)]
-    pub size: Option<String>,
+    pub stylesheet: Option<String>,
+    /// Web fonts registered with `@font-face` before the stylesheet.
+    #[serde(default)]
+    pub fonts: Vec<ThemeFont>,
+    /// Hide the hover close button in the top-right corner.
+    #[serde(default)]
+    pub hide_close_button: bool,
+    /// Hide the drag region along the top edge of the window.
+    #[serde(default)]
+    pub hide_drag_handle: bool,
+}
+
+/// A web font made available to the built-in renderer.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeFont {
+    /// Family name used in `font-family` declarations.
+    pub family: String,
+    /// Font URL — `https://…`, `data:` URI, or an asset URL.
+    pub src: String,
+    /// CSS `font-weight` value or range (e.g. `"400"`, `"100 900"`).
+    #[serde(default)]
+    pub weight: Option<String>,
+    /// CSS `font-style` value (e.g. `"italic"`).
+    #[serde(default)]
+    pub style: Option<String>,
 }
 
 fn default_true() -> bool {
@@ -48,22 +118,262 @@ fn default_true() -> bool {
 // ─── Widget UI Configuration ─────────────────────────────────────────────────
 
 /// Top-level widget config with layouts per size family.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// A widget shown in a family without its own layout uses the first
+/// layout found in `WidgetFamily::fallbacks` order.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WidgetConfig {
+    /// Schema version.  Older stored configs are upgraded on read by the
+    /// migrations registered on the plugin builder.
     #[serde(default = "default_version")]
     pub version: u32,
     pub small: Option<WidgetElement>,
     pub medium: Option<WidgetElement>,
     pub large: Option<WidgetElement>,
+    /// Layout for extra-large widgets (iPad, macOS, StandBy).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_large: Option<WidgetElement>,
+    /// Layout for circular lock screen / watch complications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessory_circular: Option<WidgetElement>,
+    /// Layout for rectangular lock screen / watch complications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessory_rectangular: Option<WidgetElement>,
+    /// Layout for the single line above the clock.  Only text and one
+    /// image are shown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessory_inline: Option<WidgetElement>,
+    /// Named property sets that elements apply with `"style": "<name>"`.
+    /// Properties set on the element itself take precedence.  A style may
+    /// extend another one through its own `style` property.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub styles: BTreeMap<String, serde_json::Map<String, serde_json::Value>>,
+    /// Design tokens referenced from element properties as `"$<name>"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<DesignTokens>,
+    /// Reusable element subtrees, instantiated with `ref` elements.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Component>,
+    /// Localized strings per locale (`strings["de"]["greeting"]`),
+    /// referenced from text properties as `{"t": "<key>", "args": {…}}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub strings: BTreeMap<String, BTreeMap<String, LocalizedString>>,
+    /// Locale tried after the requested one and before `en`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_locale: Option<String>,
 }
 
 fn default_version() -> u32 {
     1
 }
 
+impl WidgetConfig {
+    /// The layout of `family`, or of the first related family that has one.
+    pub fn layout(&self, family: WidgetFamily) -> Option<&WidgetElement> {
+        family
+            .fallbacks()
+            .iter()
+            .find_map(|f| self.own_layout(*f))
+    }
+
+    /// The layout set for exactly `family`.
+    pub fn own_layout(&self, family: WidgetFamily) -> Option<&WidgetElement> {
+        match family {
+            WidgetFamily::Small => self.small.as_ref(),
+            WidgetFamily::Medium => self.medium.as_ref(),
+            WidgetFamily::Large => self.large.as_ref(),
+            WidgetFamily::ExtraLarge => self.extra_large.as_ref(),
+            WidgetFamily::AccessoryCircular => self.accessory_circular.as_ref(),
+            WidgetFamily::AccessoryRectangular => self.accessory_rectangular.as_ref(),
+            WidgetFamily::AccessoryInline => self.accessory_inline.as_ref(),
+        }
+    }
+}
+
+/// Widget size family, named after the WidgetKit families.  Android size
+/// buckets map onto the `system` families.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WidgetFamily {
+    Small,
+    Medium,
+    Large,
+    ExtraLarge,
+    AccessoryCircular,
+    AccessoryRectangular,
+    AccessoryInline,
+}
+
+impl WidgetFamily {
+    pub const ALL: [WidgetFamily; 7] = [
+        WidgetFamily::Small,
+        WidgetFamily::Medium,
+        WidgetFamily::Large,
+        WidgetFamily::ExtraLarge,
+        WidgetFamily::AccessoryCircular,
+        WidgetFamily::AccessoryRectangular,
+        WidgetFamily::AccessoryInline,
+    ];
+
+    /// Key of the family's layout in a config, e.g. `"extraLarge"`.
+    pub fn key(self) -> &'static str {
+        match self {
+            WidgetFamily::Small => "small",
+            WidgetFamily::Medium => "medium",
+            WidgetFamily::Large => "large",
+            WidgetFamily::ExtraLarge => "extraLarge",
+            WidgetFamily::AccessoryCircular => "accessoryCircular",
+            WidgetFamily::AccessoryRectangular => "accessoryRectangular",
+            WidgetFamily::AccessoryInline => "accessoryInline",
+        }
+    }
+
+    /// Families whose layouts are tried, in order, when a widget of this
+    /// family is shown.  Starts with the family itself; system families
+    /// prefer the nearest size, accessory families the other accessory
+    /// shapes and then `small`.
+    pub fn fallbacks(self) -> &'static [WidgetFamily] {
+        use WidgetFamily::*;
+        match self {
+            Small => &[Small, Medium, Large, ExtraLarge],
+            Medium => &[Medium, Large, Small, ExtraLarge],
+            Large => &[Large, ExtraLarge, Medium, Small],
+            ExtraLarge => &[ExtraLarge, Large, Medium, Small],
+            AccessoryCircular => &[
+                AccessoryCircular,
+                AccessoryRectangular,
+                Small,
+                Medium,
+                Large,
+                ExtraLarge,
+            ],
+            AccessoryRectangular => &[
+                AccessoryRectangular,
+                AccessoryCircular,
+                Small,
+                Medium,
+                Large,
+                ExtraLarge,
+            ],
+            AccessoryInline => &[
+                AccessoryInline,
+                AccessoryRectangular,
+                AccessoryCircular,
+                Small,
+                Medium,
+                Large,
+                ExtraLarge,
+            ],
+        }
+    }
+
+    /// Typical size in points, used as the default desktop window size.
+    pub fn default_size(self) -> (f64, f64) {
+        match self {
+            WidgetFamily::Small => (170.0, 170.0),
+            WidgetFamily::Medium => (364.0, 170.0),
+            WidgetFamily::Large => (364.0, 382.0),
+            WidgetFamily::ExtraLarge => (796.0, 382.0),
+            WidgetFamily::AccessoryCircular => (76.0, 76.0),
+            WidgetFamily::AccessoryRectangular => (172.0, 76.0),
+            WidgetFamily::AccessoryInline => (257.0, 26.0),
+        }
+    }
+}
+
+/// Design tokens shared by all layouts of a config.
+///
+/// Color properties (`color`, `tint`, `fill`, `background`, …) accept
+/// `"$<name>"` from `colors`; sizes (`padding`, `spacing`, `cornerRadius`,
+/// `frame` dimensions, …) from `spacing`; `fontSize` from `fonts`.  The
+/// `font` property applies a whole font token (size, weight and design).
+/// Use `"$$"` for a literal leading `$`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DesignTokens {
+    #[serde(default)]
+    pub colors: BTreeMap<String, ColorValue>,
+    #[serde(default)]
+    pub spacing: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub fonts: BTreeMap<String, FontToken>,
+}
+
+/// One entry of a string table: plain text or plural forms.
+///
+/// Text may contain `{name}` placeholders filled from the reference's
+/// `args`.  Plural forms are chosen by the `count` argument.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum LocalizedString {
+    Text(String),
+    Plural(PluralForms),
+}
+
+/// Plural forms keyed by CLDR category.  `zero` is also used for a count
+/// of 0 in languages without a zero category.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluralForms {
+    #[serde(default)]
+    pub zero: Option<String>,
+    #[serde(default)]
+    pub one: Option<String>,
+    #[serde(default)]
+    pub two: Option<String>,
+    #[serde(default)]
+    pub few: Option<String>,
+    #[serde(default)]
+    pub many: Option<String>,
+    pub other: String,
+}
+
+/// Text shown to the user: a literal or a reference into
+/// `strings`.  Only used to describe text properties in
+/// the JSON Schema; references are resolved before deserialization.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TextValue {
+    Literal(String),
+    Localized {
+        t: String,
+        #[serde(default)]
+        args: BTreeMap<String, serde_json::Value>,
+    },
+}
+
+/// A reusable element subtree.
+///
+/// String values in `element` may contain `{{param}}` placeholders.  A
+/// value that is exactly `"{{param}}"` is replaced by the parameter value
+/// as-is (so numbers and objects work); otherwise the parameter is
+/// interpolated into the string.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Component {
+    /// Declared parameters with their default values.  A `null` default
+    /// makes the parameter required.
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_json::Value>,
+    /// The element tree, in its JSON form.
+    pub element: serde_json::Value,
+}
+
+/// A named font: any of size, weight and design.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FontToken {
+    #[serde(default)]
+    pub size: Option<f64>,
+    #[serde(default)]
+    pub weight: Option<FontWeight>,
+    #[serde(default)]
+    pub design: Option<FontDesign>,
+}
+
 /// A UI element that can be a layout container or a leaf widget.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum WidgetElement {
     // ── Layout containers ──
@@ -126,6 +436,7 @@ pub enum WidgetElement {
     // ── Leaf elements ──
     #[serde(rename = "text")]
     Text {
+        #[schemars(with = "TextValue")]
         content: String,
         #[serde(rename = "fontSize", default)]
         font_size: Option<f64>,
@@ -146,6 +457,36 @@ pub enum WidgetElement {
         #[serde(flatten)]
         style: ElementStyle,
     },
+    /// Text with per-span styling.  Element-level font and color apply to
+    /// spans that do not set their own.
+    #[serde(rename = "richText")]
+    RichText {
+        #[serde(default)]
+        spans: Vec<TextSpan>,
+        /// Markdown source parsed into `spans` before the config is stored:
+        /// `**bold**`, `*italic*` / `_italic_`, `~~strikethrough~~`,
+        /// `` `code` `` and `[link](url)`.  Backslash escapes a marker.
+        /// Parsed spans are appended after `spans`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(with = "Option<TextValue>")]
+        markdown: Option<String>,
+        #[serde(rename = "fontSize", default)]
+        font_size: Option<f64>,
+        #[serde(rename = "fontWeight", default)]
+        font_weight: Option<FontWeight>,
+        #[serde(rename = "fontDesign", default)]
+        font_design: Option<FontDesign>,
+        #[serde(rename = "textStyle", default)]
+        text_style: Option<TextStyle>,
+        #[serde(default)]
+        color: Option<ColorValue>,
+        #[serde(default)]
+        alignment: Option<TextAlignment>,
+        #[serde(rename = "lineLimit", default)]
+        line_limit: Option<u32>,
+        #[serde(flatten)]
+        style: ElementStyle,
+    },
     #[serde(rename = "image")]
     Image {
         /// SF Symbol name (Apple) or Material icon name (Android)
@@ -171,6 +512,7 @@ pub enum WidgetElement {
         #[serde(default = "default_total")]
         total: f64,
         #[serde(default)]
+        #[schemars(with = "Option<TextValue>")]
         label: Option<String>,
         #[serde(default)]
         tint: Option<ColorValue>,
@@ -189,8 +531,10 @@ pub enum WidgetElement {
         #[serde(default)]
         max: Option<f64>,
         #[serde(default)]
+        #[schemars(with = "Option<TextValue>")]
         label: Option<String>,
         #[serde(rename = "currentValueLabel", default)]
+        #[schemars(with = "Option<TextValue>")]
         current_value_label: Option<String>,
         #[serde(default)]
         tint: Option<ColorValue>,
@@ -203,6 +547,7 @@ pub enum WidgetElement {
     },
     #[serde(rename = "button")]
     Button {
+        #[schemars(with = "TextValue")]
         label: String,
         /// Deep link URL to open the app (used when no action is set)
         #[serde(default)]
@@ -210,6 +555,9 @@ pub enum WidgetElement {
         /// Action identifier — emits a `widget-action` Tauri event when tapped
         #[serde(default)]
         action: Option<String>,
+        /// Any JSON value sent with `action` as the event payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<serde_json::Value>,
         #[serde(default)]
         color: Option<ColorValue>,
         #[serde(rename = "backgroundColor", default)]
@@ -226,12 +574,47 @@ pub enum WidgetElement {
         #[serde(rename = "isOn")]
         is_on: bool,
         #[serde(default)]
+        #[schemars(with = "Option<TextValue>")]
         label: Option<String>,
         #[serde(default)]
         tint: Option<String>,
         /// Action identifier sent back to the app
         #[serde(default)]
         action: Option<String>,
+        /// Any JSON value sent with `action`.  Bound toggles send their new
+        /// state instead.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<serde_json::Value>,
+        /// Store key holding the state as `"true"` or `"false"`.  When set,
+        /// the plugin flips the key and `isOn` on tap and sends the new
+        /// state as the action payload; `isOn` is only the initial state.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        #[serde(flatten)]
+        style: ElementStyle,
+    },
+    /// Row of mutually exclusive options.  Tapping one emits `action` with
+    /// the option's `value` as payload and moves the selection in the
+    /// stored config right away, so the widget reflects the tap before the
+    /// app responds.
+    #[serde(rename = "segmented", alias = "picker")]
+    Segmented {
+        options: Vec<SegmentOption>,
+        /// Index of the selected option.  No option is selected when unset.
+        #[serde(rename = "selectedIndex", default, skip_serializing_if = "Option::is_none")]
+        selected_index: Option<usize>,
+        /// Action identifier sent back to the app.  Segmented controls are
+        /// matched by action when the selection is updated, so give each
+        /// control its own.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<String>,
+        /// Background of the selected option.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tint: Option<ColorValue>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<ColorValue>,
+        #[serde(rename = "fontSize", default, skip_serializing_if = "Option::is_none")]
+        font_size: Option<f64>,
         #[serde(flatten)]
         style: ElementStyle,
     },
@@ -248,6 +631,8 @@ pub enum WidgetElement {
     Spacer {
         #[serde(rename = "minLength", default)]
         min_length: Option<f64>,
+        #[serde(default)]
+        id: Option<String>,
     },
     #[serde(rename = "date")]
     Date {
@@ -266,13 +651,85 @@ pub enum WidgetElement {
     Chart {
         #[serde(rename = "chartType")]
         chart_type: ChartType,
-        #[serde(rename = "chartData")]
+        /// Data of a single-series chart.  Ignored when `series` is set.
+        #[serde(rename = "chartData", default)]
         chart_data: Vec<ChartDataPoint>,
+        /// Named series sharing the x axis; points are matched by index.
+        /// Pie charts show the first series.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        series: Vec<ChartSeries>,
+        /// Stack series on top of each other instead of side by side
+        /// (bar) or overlapping (area).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stacked: Option<bool>,
+        #[serde(rename = "xAxis", default, skip_serializing_if = "Option::is_none")]
+        x_axis: Option<ChartAxis>,
+        #[serde(rename = "yAxis", default, skip_serializing_if = "Option::is_none")]
+        y_axis: Option<ChartAxis>,
+        /// Horizontal rule lines, e.g. a target or limit.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        thresholds: Vec<ChartThreshold>,
+        /// Where to show series names.  Defaults to `bottom` for charts
+        /// with more than one named series.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        legend: Option<LegendPosition>,
+        /// Color of a single-series chart or of the first series.
         #[serde(default)]
         tint: Option<ColorValue>,
         #[serde(flatten)]
         style: ElementStyle,
     },
+    /// Compact trend line without axes or labels.  iOS and Android draw it
+    /// as an unlabelled `chart`.
+    #[serde(rename = "sparkline")]
+    Sparkline {
+        values: Vec<f64>,
+        #[serde(rename = "sparklineStyle", default, skip_serializing_if = "Option::is_none")]
+        sparkline_style: Option<SparklineStyle>,
+        /// Mark the last value with a dot (line, area) or the highlight
+        /// color (bar).
+        #[serde(rename = "highlightLast", default, skip_serializing_if = "Option::is_none")]
+        highlight_last: Option<bool>,
+        /// Color of the last value when highlighted.  Defaults to `tint`.
+        #[serde(rename = "highlightColor", default, skip_serializing_if = "Option::is_none")]
+        highlight_color: Option<ColorValue>,
+        #[serde(default)]
+        tint: Option<ColorValue>,
+        #[serde(flatten)]
+        style: ElementStyle,
+    },
+    /// A headline number with unit, change indicator and caption.  iOS and
+    /// Android draw it as a stack of texts.
+    #[serde(rename = "stat")]
+    Stat {
+        /// The formatted value, e.g. `"1,284"`.
+        #[schemars(with = "TextValue")]
+        value: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(with = "Option<TextValue>")]
+        unit: Option<String>,
+        /// Change since the previous period, shown with an arrow and colored
+        /// green when up and red when down.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        delta: Option<f64>,
+        /// Suffix of the delta, e.g. `"%"`.
+        #[serde(rename = "deltaUnit", default, skip_serializing_if = "Option::is_none")]
+        delta_unit: Option<String>,
+        /// Color decreases green and increases red, for values such as
+        /// latency or cost.
+        #[serde(rename = "lowerIsBetter", default, skip_serializing_if = "Option::is_none")]
+        lower_is_better: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(with = "Option<TextValue>")]
+        caption: Option<String>,
+        /// Size of the value.  Default: 28.
+        #[serde(rename = "fontSize", default, skip_serializing_if = "Option::is_none")]
+        font_size: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<ColorValue>,
+        #[serde(flatten)]
+        style: ElementStyle,
+    },
     /// Android-only collection list rendered via RemoteViewsService/ListView.
     #[serde(rename = "list")]
     List {
@@ -301,6 +758,9 @@ pub enum WidgetElement {
         /// Action identifier — emits `widget-action` event
         #[serde(default)]
         action: Option<String>,
+        /// Any JSON value sent with `action` as the event payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<serde_json::Value>,
         #[serde(flatten)]
         style: ElementStyle,
     },
@@ -355,6 +815,7 @@ pub enum WidgetElement {
     /// Convenience element combining an SF Symbol icon with text.
     #[serde(rename = "label")]
     Label {
+        #[schemars(with = "TextValue")]
         text: String,
         #[serde(rename = "systemName")]
         system_name: String,
@@ -371,6 +832,85 @@ pub enum WidgetElement {
         #[serde(flatten)]
         style: ElementStyle,
     },
+
+    /// Instance of an entry in `components`.  Expanded
+    /// before the config is stored; style properties set here override
+    /// those of the component's root element.
+    #[serde(rename = "ref")]
+    Ref {
+        component: String,
+        #[serde(default)]
+        params: BTreeMap<String, serde_json::Value>,
+        #[serde(flatten)]
+        style: ElementStyle,
+    },
+}
+
+impl WidgetElement {
+    /// Stable identity of the element, if one was assigned.
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            WidgetElement::Spacer { id, .. } => id.as_deref(),
+            _ => self.style().and_then(|style| style.id.as_deref()),
+        }
+    }
+
+    /// Shared style properties.  `None` for spacers, which have none.
+    pub fn style(&self) -> Option<&ElementStyle> {
+        match self {
+            WidgetElement::Spacer { .. } => None,
+            WidgetElement::VStack { style, .. }
+            | WidgetElement::HStack { style, .. }
+            | WidgetElement::ZStack { style, .. }
+            | WidgetElement::Grid { style, .. }
+            | WidgetElement::Container { style, .. }
+            | WidgetElement::Text { style, .. }
+            | WidgetElement::RichText { style, .. }
+            | WidgetElement::Image { style, .. }
+            | WidgetElement::Progress { style, .. }
+            | WidgetElement::Gauge { style, .. }
+            | WidgetElement::Button { style, .. }
+            | WidgetElement::Toggle { style, .. }
+            | WidgetElement::Segmented { style, .. }
+            | WidgetElement::Divider { style, .. }
+            | WidgetElement::Date { style, .. }
+            | WidgetElement::Chart { style, .. }
+            | WidgetElement::Sparkline { style, .. }
+            | WidgetElement::Stat { style, .. }
+            | WidgetElement::List { style, .. }
+            | WidgetElement::Link { style, .. }
+            | WidgetElement::Shape { style, .. }
+            | WidgetElement::Timer { style, .. }
+            | WidgetElement::Canvas { style, .. }
+            | WidgetElement::Label { style, .. }
+            | WidgetElement::Ref { style, .. } => Some(style),
+        }
+    }
+
+    /// Child elements of containers and links.
+    pub fn children(&self) -> &[WidgetElement] {
+        match self {
+            WidgetElement::VStack { children, .. }
+            | WidgetElement::HStack { children, .. }
+            | WidgetElement::ZStack { children, .. }
+            | WidgetElement::Grid { children, .. }
+            | WidgetElement::Container { children, .. }
+            | WidgetElement::Link { children, .. } => children,
+            _ => &[],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> &mut [WidgetElement] {
+        match self {
+            WidgetElement::VStack { children, .. }
+            | WidgetElement::HStack { children, .. }
+            | WidgetElement::ZStack { children, .. }
+            | WidgetElement::Grid { children, .. }
+            | WidgetElement::Container { children, .. }
+            | WidgetElement::Link { children, .. } => children,
+            _ => &mut [],
+        }
+    }
 }
 
 fn default_columns() -> u32 {
@@ -382,9 +922,16 @@ fn default_total() -> f64 {
 
 // ─── Shared style applied to any element ─────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ElementStyle {
+    /// Stable element identity.  Lets the desktop renderer keep unchanged
+    /// nodes across updates and lets patches address the element.
+    pub id: Option<String>,
+    /// Name of an entry in `styles` to apply.  Resolved
+    /// before the config is stored, so renderers never see it.
+    #[serde(rename = "style", default, skip_serializing_if = "Option::is_none")]
+    pub named_style: Option<String>,
     pub padding: Option<PaddingValue>,
     pub background: Option<BackgroundValue>,
     #[serde(rename = "cornerRadius")]
@@ -398,13 +945,49 @@ pub struct ElementStyle {
     pub clip_shape: Option<ClipShape>,
     /// Layout weight for flexible sizing inside stacks (like Android `layout_weight`).
     pub flex: Option<f64>,
+    /// Text read by screen readers instead of the element's content.
+    /// Required for images and icon-only buttons to be announced usefully.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<TextValue>")]
+    pub accessibility_label: Option<String>,
+    /// Describes what happens when the element is activated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<TextValue>")]
+    pub accessibility_hint: Option<String>,
+    /// Hide the element and its children from screen readers, e.g. for
+    /// decorative images.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessibility_hidden: Option<bool>,
+    /// Semantic role announced by screen readers.  Defaults to the role
+    /// implied by the element type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessibility_role: Option<AccessibilityRole>,
+}
+
+/// Semantic role of an element for assistive technologies.  Maps to
+/// accessibility traits on Apple, semantics on Android and ARIA roles on
+/// desktop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AccessibilityRole {
+    Header,
+    Button,
+    Link,
+    Image,
+    Text,
+    Toggle,
+    Progress,
+    /// Read the element and its children as a single item.
+    Group,
+    /// Content that updates on its own, e.g. a timer.
+    Live,
 }
 
 /// Color value — hex string, semantic name, or adaptive `{ light, dark }` pair.
 ///
 /// Semantic names: `"label"`, `"secondaryLabel"`, `"systemBackground"`,
 /// `"secondarySystemBackground"`, `"accent"`, `"separator"`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum ColorValue {
     Solid(String),
@@ -412,7 +995,7 @@ pub enum ColorValue {
 }
 
 /// Clip shape for content masking.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ClipShape {
     Circle,
@@ -421,7 +1004,7 @@ pub enum ClipShape {
 }
 
 /// Semantic text style — respects Dynamic Type / accessibility settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TextStyle {
     LargeTitle,
@@ -438,7 +1021,7 @@ pub enum TextStyle {
 }
 
 /// Background: solid color string, adaptive pair, gradient, or material blur.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum BackgroundValue {
     Solid(String),
@@ -446,7 +1029,7 @@ pub enum BackgroundValue {
     Adaptive { light: String, dark: String },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GradientConfig {
     /// `"linear"`, `"radial"`, or `"angular"`
@@ -458,7 +1041,7 @@ pub struct GradientConfig {
     pub direction: Option<GradientDirection>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum GradientType {
     Linear,
@@ -466,7 +1049,7 @@ pub enum GradientType {
     Angular,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum GradientDirection {
     TopToBottom,
@@ -477,7 +1060,7 @@ pub enum GradientDirection {
     TopTrailingToBottomLeading,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ShadowConfig {
     #[serde(default)]
@@ -490,7 +1073,7 @@ pub struct ShadowConfig {
     pub y: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum PaddingValue {
     Uniform(f64),
@@ -502,7 +1085,7 @@ pub enum PaddingValue {
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FrameConfig {
     pub width: Option<f64>,
@@ -513,14 +1096,14 @@ pub struct FrameConfig {
     pub max_height: Option<FrameDimension>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum FrameDimension {
     Fixed(f64),
     Keyword(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BorderConfig {
     pub color: String,
@@ -534,7 +1117,7 @@ fn default_border_width() -> f64 {
 
 // ─── Enums ───────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum FontWeight {
     Ultralight,
@@ -548,7 +1131,7 @@ pub enum FontWeight {
     Black,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum FontDesign {
     Default,
@@ -557,7 +1140,7 @@ pub enum FontDesign {
     Serif,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TextAlignment {
     Leading,
@@ -565,7 +1148,7 @@ pub enum TextAlignment {
     Trailing,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum HorizontalAlignment {
     Leading,
@@ -573,7 +1156,7 @@ pub enum HorizontalAlignment {
     Trailing,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum VerticalAlignment {
     Top,
@@ -581,28 +1164,36 @@ pub enum VerticalAlignment {
     Bottom,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ContentMode {
     Fit,
     Fill,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ProgressStyle {
     Linear,
     Circular,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SparklineStyle {
+    Line,
+    Bar,
+    Area,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum GaugeStyle {
     Circular,
     Linear,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum DateStyle {
     Time,
@@ -612,7 +1203,7 @@ pub enum DateStyle {
     Timer,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ChartType {
     Bar,
@@ -621,7 +1212,7 @@ pub enum ChartType {
     Pie,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ShapeType {
     Circle,
@@ -629,36 +1220,306 @@ pub enum ShapeType {
     Rectangle,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TimerCounting {
     Up,
     Down,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ChartDataPoint {
+    #[schemars(with = "TextValue")]
+    pub label: String,
+    pub value: f64,
+    pub color: Option<ColorValue>,
+}
+
+/// An option of a `segmented` control.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentOption {
+    #[schemars(with = "TextValue")]
     pub label: String,
+    /// Sent as the action payload when the option is tapped.
+    pub value: String,
+    /// SF Symbol shown before the label on iOS and macOS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_name: Option<String>,
+}
+
+/// A named data series of a chart.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartSeries {
+    /// Name shown in the legend.
+    #[serde(default)]
+    #[schemars(with = "TextValue")]
+    pub name: String,
+    pub data: Vec<ChartDataPoint>,
+    /// Series color.  Defaults to a palette color by position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorValue>,
+}
+
+/// Labels, gridlines and range of a chart axis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartAxis {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<TextValue>")]
+    pub title: Option<String>,
+    /// Show tick labels.  Default: on for the x axis of bar charts, off
+    /// otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gridlines: Option<bool>,
+    /// Number of labelled ticks on the y axis.  Default: 3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick_count: Option<u32>,
+    /// Lower bound of the y axis instead of the data minimum (or 0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Upper bound of the y axis instead of the data maximum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// A horizontal rule line across a chart at `value`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartThreshold {
     pub value: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<ColorValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<TextValue>")]
+    pub label: Option<String>,
+    /// Draw a dashed line.  Default: `true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dashed: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LegendPosition {
+    Top,
+    Bottom,
+    Hidden,
+}
+
+/// A run of text inside a `richText` element.  Unset properties inherit
+/// from the element.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextSpan {
+    #[schemars(with = "TextValue")]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_weight: Option<FontWeight>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underline: Option<bool>,
+    /// Render in a monospaced font, e.g. for inline code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monospace: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorValue>,
+    /// Deep-link URL opened when the span is tapped.  Widgets open the app
+    /// with it; the desktop renderer opens it like a `link` element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListItem {
+    #[schemars(with = "TextValue")]
     pub text: String,
     #[serde(default)]
     pub checked: Option<bool>,
     #[serde(default)]
     pub action: Option<String>,
+    /// Any JSON value sent with `action`.
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+}
+
+// ─── Widget actions ─────────────────────────────────────────────────────────
+
+/// A tap on a widget element with an `action`, as delivered by the
+/// `widget-action` event and `poll_pending_actions`.
+///
+/// Only `action` and `payload` are always present; the other fields are
+/// filled in where the platform knows them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetActionEvent {
+    /// Queue id, set on queued actions: pass it to `ack_actions` once the
+    /// action is handled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Unique per tap, set by the widget or, when it sends none, by the
+    /// plugin.  An action arriving again with the same nonce is a retry
+    /// and is dropped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    pub action: String,
+    /// The element's payload: any JSON value.  Payloads set as strings,
+    /// including JSON encoded in a string, arrive as strings.
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+    /// Group of the widget that was tapped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Layout family the widget showed, e.g. `small`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Label of the desktop window the action came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    /// `id` of the tapped element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element_id: Option<String>,
+    /// Time of the tap in milliseconds since the Unix epoch.
     #[serde(default)]
-    pub payload: Option<String>,
+    pub timestamp: u64,
+    /// Failed runs of the action's Rust handler, for actions queued for a
+    /// retry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
+}
+
+impl WidgetActionEvent {
+    pub fn new(action: impl Into<String>, payload: Option<serde_json::Value>) -> Self {
+        Self {
+            id: None,
+            nonce: None,
+            action: action.into(),
+            payload,
+            group: None,
+            kind: None,
+            window: None,
+            element_id: None,
+            timestamp: 0,
+            attempts: None,
+        }
+    }
+}
+
+/// Payload of the `widget-deeplink` event, sent when a desktop widget opens
+/// a URL with one of the app's deep-link schemes.
+///
+/// `myapp://settings/theme?mode=dark` has the route `settings/theme` and
+/// the params `{ "mode": "dark" }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetDeepLinkEvent {
+    pub url: String,
+    /// Host and path of the URL, without leading or trailing slashes.
+    pub route: String,
+    /// Decoded query parameters; of repeated ones, the last.
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// Group of the widget that was tapped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Label of the desktop window the link came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    /// `id` of the tapped element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub element_id: Option<String>,
+}
+
+// ─── Permission scopes ──────────────────────────────────────────────────────
+
+/// An entry of the widgets plugin's permission scope, limiting what a
+/// window may do with the plugin's commands.
+///
+/// Each list restricts one part of a call; an empty list does not restrict
+/// it.  Items are exact names, or prefixes ending in `*`.  The group
+/// `"$window"` stands for the group of the calling widget window, as given
+/// to `create_widget_window`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetScope {
+    /// Groups that may be read and written, e.g. `"group.com.example.app"`
+    /// or `"$window"`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    /// Store keys that `set_items` and `get_items` may use, e.g.
+    /// `"weather_*"`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<String>,
+    /// Actions that `widget_action` may emit.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
+}
+
+// ─── Config patches ─────────────────────────────────────────────────────────
+
+/// A single JSON Patch (RFC 6902) operation against a widget config.
+///
+/// Paths are JSON Pointers (RFC 6901) into the config's JSON form,
+/// e.g. `/small/children/0/content`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum PatchOp {
+    Add {
+        path: String,
+        value: serde_json::Value,
+    },
+    Remove {
+        path: String,
+    },
+    Replace {
+        path: String,
+        value: serde_json::Value,
+    },
+    Move {
+        from: String,
+        path: String,
+    },
+    Copy {
+        from: String,
+        path: String,
+    },
+    /// Fails the whole patch unless the value at `path` equals `value`.
+    Test {
+        path: String,
+        value: serde_json::Value,
+    },
+}
+
+/// Merge `props` into the element whose `id` matches.  A `null` prop
+/// removes that property from the element.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementPatch {
+    pub id: String,
+    pub props: serde_json::Map<String, serde_json::Value>,
+}
+
+/// One update accepted by `patch_widget_config`: a JSON Patch operation
+/// or an element-id addressed props merge.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ConfigPatch {
+    Op(PatchOp),
+    Element(ElementPatch),
 }
 
 // ─── Canvas drawing commands ────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "draw", rename_all = "camelCase")]
 pub enum CanvasDrawCommand {
     #[serde(rename = "circle")]
@@ -721,6 +1582,7 @@ pub enum CanvasDrawCommand {
     Text {
         x: f64,
         y: f64,
+        #[schemars(with = "TextValue")]
         content: String,
         #[serde(rename = "fontSize", default)]
         font_size: Option<f64>,
@@ -0,0 +1,333 @@
+//! JSON Patch helpers for widget configs.
+//!
+//! [`diff`] computes the operations that turn one config into another, so
+//! desktop widget windows can receive a small `widget-config-patch` event
+//! instead of re-rendering a whole config.  [`apply`] runs RFC 6902
+//! operations and element-id updates against a stored config.
+
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+use crate::models::{ConfigPatch, ElementPatch, PatchOp};
+
+/// Compute the patch operations that transform `old` into `new`.
+///
+/// Objects are compared key by key and arrays index by index; trailing
+/// array items are added or removed.  Returns an empty list when both
+/// values are equal.
+pub fn diff(old: &Value, new: &Value) -> Vec<PatchOp> {
+    let mut ops = Vec::new();
+    diff_into(old, new, "", &mut ops);
+    ops
+}
+
+fn diff_into(old: &Value, new: &Value, path: &str, ops: &mut Vec<PatchOp>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, old_val) in a {
+                let child = format!("{path}/{}", escape_token(key));
+                match b.get(key) {
+                    Some(new_val) => diff_into(old_val, new_val, &child, ops),
+                    None => ops.push(PatchOp::Remove { path: child }),
+                }
+            }
+            for (key, new_val) in b {
+                if !a.contains_key(key) {
+                    ops.push(PatchOp::Add {
+                        path: format!("{path}/{}", escape_token(key)),
+                        value: new_val.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let common = a.len().min(b.len());
+            for i in 0..common {
+                diff_into(&a[i], &b[i], &format!("{path}/{i}"), ops);
+            }
+            for (i, value) in b.iter().enumerate().skip(common) {
+                ops.push(PatchOp::Add {
+                    path: format!("{path}/{i}"),
+                    value: value.clone(),
+                });
+            }
+            // Remove from the end so earlier indices stay valid.
+            for i in (common..a.len()).rev() {
+                ops.push(PatchOp::Remove {
+                    path: format!("{path}/{i}"),
+                });
+            }
+        }
+        _ if old == new => {}
+        _ => ops.push(PatchOp::Replace {
+            path: path.to_string(),
+            value: new.clone(),
+        }),
+    }
+}
+
+/// Apply `patches` to `doc` in order.
+///
+/// The document is only modified when every operation succeeds, so a
+/// failing `test` or a bad path leaves it untouched.
+pub fn apply(doc: &mut Value, patches: &[ConfigPatch]) -> crate::Result<()> {
+    let mut work = doc.clone();
+    for patch in patches {
+        match patch {
+            ConfigPatch::Op(op) => apply_op(&mut work, op)?,
+            ConfigPatch::Element(update) => apply_element(&mut work, update)?,
+        }
+    }
+    *doc = work;
+    Ok(())
+}
+
+fn apply_op(doc: &mut Value, op: &PatchOp) -> crate::Result<()> {
+    match op {
+        PatchOp::Add { path, value } => add(doc, path, value.clone()),
+        PatchOp::Remove { path } => remove(doc, path).map(|_| ()),
+        PatchOp::Replace { path, value } => {
+            let target = doc.pointer_mut(path).ok_or_else(|| missing(path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOp::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(Error::invalid(
+                    path,
+                    format!("cannot move `{from}` into its own child `{path}`"),
+                ));
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        PatchOp::Copy { from, path } => {
+            let value = doc.pointer(from).cloned().ok_or_else(|| missing(from))?;
+            add(doc, path, value)
+        }
+        PatchOp::Test { path, value } => match doc.pointer(path) {
+            Some(current) if current == value => Ok(()),
+            _ => Err(Error::invalid(path, format!("patch test failed at `{path}`"))),
+        },
+    }
+}
+
+fn apply_element(doc: &mut Value, update: &ElementPatch) -> crate::Result<()> {
+    let element = find_by_id(doc, &update.id)
+        .ok_or_else(|| Error::not_found(format!("no element with id `{}`", update.id)))?;
+    for (key, value) in &update.props {
+        if value.is_null() {
+            element.remove(key);
+        } else {
+            element.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Find the first object whose `id` property equals `id`, depth-first.
+pub(crate) fn find_by_id<'a>(value: &'a mut Value, id: &str) -> Option<&'a mut Map<String, Value>> {
+    match value {
+        Value::Object(map) => {
+            if map.get("id").and_then(Value::as_str) == Some(id) {
+                return Some(map);
+            }
+            map.values_mut().find_map(|v| find_by_id(v, id))
+        }
+        Value::Array(items) => items.iter_mut().find_map(|v| find_by_id(v, id)),
+        _ => None,
+    }
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> crate::Result<()> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (parent, token) = split_pointer(path)?;
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(map)) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Some(Value::Array(items)) => {
+            let index = if token == "-" {
+                items.len()
+            } else {
+                parse_index(&token, items.len() + 1, path)?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(missing(parent)),
+    }
+}
+
+fn remove(doc: &mut Value, path: &str) -> crate::Result<Value> {
+    let (parent, token) = split_pointer(path)?;
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(map)) => map.remove(&token).ok_or_else(|| missing(path)),
+        Some(Value::Array(items)) => {
+            let index = parse_index(&token, items.len(), path)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(missing(path)),
+    }
+}
+
+/// Split a JSON Pointer into its parent pointer and unescaped last token.
+fn split_pointer(path: &str) -> crate::Result<(&str, String)> {
+    let idx = path
+        .rfind('/')
+        .ok_or_else(|| Error::invalid(path, format!("invalid JSON pointer `{path}`")))?;
+    let token = path[idx + 1..].replace("~1", "/").replace("~0", "~");
+    Ok((&path[..idx], token))
+}
+
+fn parse_index(token: &str, len: usize, path: &str) -> crate::Result<usize> {
+    token
+        .parse::<usize>()
+        .ok()
+        .filter(|i| *i < len)
+        .ok_or_else(|| {
+            Error::invalid(path, format!("array index out of range at `{path}`"))
+        })
+}
+
+fn missing(path: &str) -> Error {
+    Error::invalid(path, format!("no value at `{path}`"))
+}
+
+/// Escape a key for use as a JSON Pointer reference token.
+pub fn escape_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn patches(value: Value) -> Vec<ConfigPatch> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// `new`, rebuilt from `old` with the ops of `diff`.
+    fn round_trip(old: Value, new: &Value) -> Value {
+        let ops = diff(&old, new)
+            .into_iter()
+            .map(ConfigPatch::Op)
+            .collect::<Vec<_>>();
+        let mut doc = old;
+        apply(&mut doc, &ops).unwrap();
+        doc
+    }
+
+    #[test]
+    fn arrays_are_diffed_index_by_index() {
+        let old = json!({ "items": ["a", "b", "c"] });
+        let new = json!({ "items": ["a", "x"] });
+        let ops = serde_json::to_value(diff(&old, &new)).unwrap();
+        assert_eq!(
+            ops,
+            json!([
+                { "op": "replace", "path": "/items/1", "value": "x" },
+                { "op": "remove", "path": "/items/2" }
+            ])
+        );
+        assert_eq!(round_trip(old, &new), new);
+
+        let old = json!([1]);
+        let new = json!([1, 2, 3]);
+        assert_eq!(diff(&old, &new).len(), 2);
+        assert_eq!(round_trip(old, &new), new);
+    }
+
+    #[test]
+    fn equal_values_have_no_diff() {
+        let doc = json!({ "small": { "type": "text", "content": "hi" } });
+        assert!(diff(&doc, &doc).is_empty());
+    }
+
+    #[test]
+    fn keys_are_escaped_in_paths() {
+        let old = json!({ "a/b": 1, "c~d": 1 });
+        let new = json!({ "a/b": 2, "c~d": 2 });
+        let ops = serde_json::to_value(diff(&old, &new)).unwrap();
+        let paths: Vec<&str> = ops
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|op| op["path"].as_str().unwrap())
+            .collect();
+        assert!(paths.contains(&"/a~1b"));
+        assert!(paths.contains(&"/c~0d"));
+        assert_eq!(round_trip(old, &new), new);
+        assert_eq!(escape_token("~/"), "~0~1");
+    }
+
+    #[test]
+    fn failing_patches_leave_the_document_untouched() {
+        let mut doc = json!({ "title": "old", "items": [1] });
+        let before = doc.clone();
+        let result = apply(
+            &mut doc,
+            &patches(json!([
+                { "op": "replace", "path": "/title", "value": "new" },
+                { "op": "add", "path": "/items/-", "value": 2 },
+                { "op": "test", "path": "/title", "value": "old" }
+            ])),
+        );
+        assert!(matches!(result, Err(Error::Validation { .. })));
+        assert_eq!(doc, before);
+
+        let result = apply(
+            &mut doc,
+            &patches(json!([
+                { "op": "replace", "path": "/title", "value": "new" },
+                { "op": "remove", "path": "/items/5" }
+            ])),
+        );
+        assert!(result.is_err());
+        assert_eq!(doc, before);
+    }
+
+    #[test]
+    fn element_patches_merge_props_by_id() {
+        let mut doc = json!({
+            "small": { "type": "vstack", "children": [
+                { "type": "text", "id": "title", "content": "a", "color": "red" }
+            ] }
+        });
+        apply(
+            &mut doc,
+            &patches(json!([{ "id": "title", "props": { "content": "b", "color": null } }])),
+        )
+        .unwrap();
+        assert_eq!(
+            doc["small"]["children"][0],
+            json!({ "type": "text", "id": "title", "content": "b" })
+        );
+    }
+
+    #[test]
+    fn unknown_element_ids_are_not_found() {
+        let mut doc = json!({ "small": { "type": "text", "id": "title" } });
+        let result = apply(
+            &mut doc,
+            &patches(json!([{ "id": "missing", "props": { "content": "b" } }])),
+        );
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn moves_into_their_own_child_are_refused() {
+        let mut doc = json!({ "a": { "b": 1 } });
+        let result = apply(
+            &mut doc,
+            &patches(json!([{ "op": "move", "from": "/a", "path": "/a/c" }])),
+        );
+        assert!(result.is_err());
+        assert_eq!(doc, json!({ "a": { "b": 1 } }));
+    }
+}
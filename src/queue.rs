@@ -0,0 +1,134 @@
+//! Durable queue of widget actions.
+//!
+//! On desktop actions are kept under `__widget_action_queue__` in the
+//! group's data file.  On mobile they are kept in a file per group in the
+//! app's data directory, which `poll_pending_actions` moves the taps queued
+//! by native widgets into.  Every action is queued before it is delivered.
+//! One handled by a Rust handler is acknowledged at once; one whose handler
+//! fails is retried by `poll_pending_actions` until it succeeds or has
+//! failed [`Retention::max_attempts`] times.  One emitted as the
+//! `widget-action` event stays queued until the app acknowledges it by id
+//! or `poll_pending_actions` returns it, so an app that was not listening
+//! when the action fired still gets it.
+//!
+//! With `Builder::manual_action_ack`, actions returned by
+//! `poll_pending_actions` stay queued too, until acknowledged.  Delivery is
+//! then at-least-once: an action can be seen twice if the app handles it
+//! but exits before acknowledging it.  [`Retention`] bounds the queue for
+//! apps that never acknowledge.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::actions::now_millis;
+use crate::models::WidgetActionEvent;
+
+/// Store key holding the queue as a JSON array of events.
+pub const QUEUE_KEY: &str = "__widget_action_queue__";
+
+/// Limits of a queue.  Entries beyond `max_actions` are dropped oldest
+/// first; entries older than `max_age` are dropped when the queue is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    pub max_actions: usize,
+    pub max_age: Duration,
+    /// Failed handler runs after which an action is dropped.
+    pub max_attempts: u32,
+}
+
+impl Default for Retention {
+    /// 500 actions, kept for a week; handlers are tried three times.
+    fn default() -> Self {
+        Self {
+            max_actions: 500,
+            max_age: Duration::from_secs(7 * 24 * 60 * 60),
+            max_attempts: 3,
+        }
+    }
+}
+
+/// A new queue id.  Ids are the enqueue time plus a per-process counter,
+/// so they are unique across restarts and sort in queue order.
+pub fn next_id() -> String {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+    format!("{:012x}-{:04x}", now_millis(), seq & 0xffff)
+}
+
+/// Append `event` with a fresh id and apply `retention`.  Returns the
+/// event as queued.
+pub fn push(
+    queue: &mut Vec<WidgetActionEvent>,
+    mut event: WidgetActionEvent,
+    retention: Retention,
+) -> WidgetActionEvent {
+    event.id = Some(next_id());
+    queue.push(event.clone());
+    prune(queue, retention);
+    event
+}
+
+/// Drop entries older than `retention.max_age`, then the oldest entries
+/// beyond `retention.max_actions`.
+pub fn prune(queue: &mut Vec<WidgetActionEvent>, retention: Retention) {
+    let cutoff = now_millis().saturating_sub(retention.max_age.as_millis() as u64);
+    queue.retain(|event| event.timestamp >= cutoff);
+    let excess = queue.len().saturating_sub(retention.max_actions);
+    queue.drain(..excess);
+}
+
+/// Count a failed handler run of the entry with `id`.  Returns the entry,
+/// removed from the queue, once it has failed `retention.max_attempts`
+/// times.
+pub fn fail(
+    queue: &mut Vec<WidgetActionEvent>,
+    id: &str,
+    retention: Retention,
+) -> Option<WidgetActionEvent> {
+    let index = queue.iter().position(|e| e.id.as_deref() == Some(id))?;
+    let attempts = queue[index].attempts.unwrap_or(0) + 1;
+    queue[index].attempts = Some(attempts);
+    (attempts >= retention.max_attempts).then(|| queue.remove(index))
+}
+
+/// Remove the entries with the given ids.  Returns how many were removed;
+/// unknown ids, e.g. of actions already acknowledged, are ignored.
+pub fn ack(queue: &mut Vec<WidgetActionEvent>, ids: &[String]) -> usize {
+    let before = queue.len();
+    queue.retain(|event| event.id.as_ref().map_or(true, |id| !ids.contains(id)));
+    before - queue.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued(retention: Retention) -> (Vec<WidgetActionEvent>, String) {
+        let mut queue = Vec::new();
+        let mut event = WidgetActionEvent::new("refresh", None);
+        event.timestamp = now_millis();
+        let id = push(&mut queue, event, retention).id.unwrap();
+        (queue, id)
+    }
+
+    #[test]
+    fn failing_actions_are_dropped_after_the_retry_limit() {
+        let retention = Retention::default();
+        let (mut queue, id) = queued(retention);
+        assert!(fail(&mut queue, &id, retention).is_none());
+        assert!(fail(&mut queue, &id, retention).is_none());
+        assert_eq!(queue[0].attempts, Some(2));
+        let dropped = fail(&mut queue, &id, retention).unwrap();
+        assert_eq!(dropped.attempts, Some(3));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn unknown_ids_are_ignored() {
+        let retention = Retention::default();
+        let (mut queue, _) = queued(retention);
+        assert!(fail(&mut queue, "missing", retention).is_none());
+        assert_eq!(ack(&mut queue, &["missing".into()]), 0);
+        assert_eq!(queue.len(), 1);
+    }
+}
@@ -0,0 +1,592 @@
+//! Resolution of config-level definitions into a plain config.
+//!
+//! A config as written by the app may use `components`, named `styles`,
+//! design `tokens` and localized `strings`.  [`resolve`] expands them on the
+//! raw JSON and validates the result against [`WidgetConfig`], so native
+//! renderers and the desktop renderer only ever receive plain element trees.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+use serde_path_to_error::Segment;
+
+use crate::error::{Error, ValidationIssue};
+use crate::i18n;
+use crate::models::{Component, DesignTokens, LocalizedString, WidgetConfig};
+use crate::patch::escape_token;
+
+/// Top-level keys that hold definitions rather than layouts.
+const DEFINITION_KEYS: &[&str] = &[
+    "styles",
+    "tokens",
+    "components",
+    "strings",
+    "defaultLocale",
+];
+
+/// Element properties holding app data, passed through untouched.
+const OPAQUE_KEYS: &[&str] = &["payload", "params"];
+
+/// Properties whose `"$name"` values come from `tokens.colors`.
+const COLOR_KEYS: &[&str] = &[
+    "color",
+    "tint",
+    "fill",
+    "stroke",
+    "background",
+    "backgroundColor",
+    "iconColor",
+    "light",
+    "dark",
+    "colors",
+];
+
+/// Properties whose `"$name"` values come from `tokens.spacing`.
+const SPACING_KEYS: &[&str] = &[
+    "padding",
+    "top",
+    "bottom",
+    "leading",
+    "trailing",
+    "spacing",
+    "rowSpacing",
+    "cornerRadius",
+    "minLength",
+    "thickness",
+    "strokeWidth",
+    "size",
+    "width",
+    "height",
+    "maxWidth",
+    "maxHeight",
+    "radius",
+];
+
+/// Expand components, styles, tokens and strings (for `locale`) in
+/// `source` and validate the result.
+///
+/// Returns the typed config together with its JSON form, which is what
+/// gets stored for native renderers.  Every unknown reference is reported
+/// with its JSON Pointer path.
+pub fn resolve(source: &Value, locale: &str) -> crate::Result<(WidgetConfig, Value)> {
+    let mut doc = source.clone();
+    let root = doc
+        .as_object_mut()
+        .ok_or_else(|| Error::invalid("", "widget config must be a JSON object"))?;
+
+    let styles = match root.remove("styles") {
+        Some(Value::Object(styles)) => styles,
+        Some(Value::Null) | None => Map::new(),
+        Some(_) => return Err(Error::invalid("/styles", "`styles` must be an object")),
+    };
+    let tokens: DesignTokens = match root.remove("tokens") {
+        Some(Value::Null) | None => DesignTokens::default(),
+        Some(tokens) => serde_json::from_value(tokens)
+            .map_err(|e| Error::invalid("/tokens", format!("invalid `tokens`: {e}")))?,
+    };
+    let components: BTreeMap<String, Component> = match root.remove("components") {
+        Some(Value::Null) | None => BTreeMap::new(),
+        Some(components) => serde_json::from_value(components)
+            .map_err(|e| Error::invalid("/components", format!("invalid `components`: {e}")))?,
+    };
+    let strings: BTreeMap<String, BTreeMap<String, LocalizedString>> =
+        match root.remove("strings") {
+            Some(Value::Null) | None => BTreeMap::new(),
+            Some(strings) => serde_json::from_value(strings).map_err(|e| {
+                Error::invalid("/strings", format!("invalid `strings`: {e}"))
+            })?,
+        };
+    let default_locale = root.remove("defaultLocale");
+    let locales = i18n::fallback_chain(locale, default_locale.as_ref().and_then(Value::as_str))
+        .into_iter()
+        .filter_map(|tag| {
+            let (_, table) = strings.iter().find(|(l, _)| i18n::normalize(l) == tag)?;
+            Some((tag, table))
+        })
+        .collect();
+
+    let cx = Context {
+        styles: &styles,
+        tokens: &tokens,
+        components: &components,
+        locales,
+    };
+    let mut errors = Vec::new();
+    for (key, layout) in root.iter_mut() {
+        if key != "version" && !DEFINITION_KEYS.contains(&key.as_str()) {
+            let path = format!("/{key}");
+            cx.expand_refs(layout, &path, &mut Vec::new(), &mut errors);
+            cx.node(layout, key, &path, &mut errors);
+        }
+    }
+    if !errors.is_empty() {
+        let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+        return Err(Error::Validation {
+            message: format!("invalid widget config: {}", messages.join("; ")),
+            issues: errors,
+        });
+    }
+
+    let config = validate(doc.clone())?;
+    Ok((config, doc))
+}
+
+/// Deserialize a resolved config, reporting where deserialization failed.
+pub(crate) fn validate(doc: Value) -> crate::Result<WidgetConfig> {
+    serde_path_to_error::deserialize(doc).map_err(|e| {
+        let path = e.path().to_string();
+        let pointer: String = e
+            .path()
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Seq { index } => Some(format!("/{index}")),
+                Segment::Map { key } => Some(format!("/{}", escape_token(key))),
+                Segment::Enum { .. } | Segment::Unknown => None,
+            })
+            .collect();
+        let inner = e.into_inner().to_string();
+        Error::Validation {
+            message: format!("invalid widget config at `{path}`: {inner}"),
+            issues: vec![ValidationIssue {
+                path: pointer,
+                message: inner,
+            }],
+        }
+    })
+}
+
+struct Context<'a> {
+    styles: &'a Map<String, Value>,
+    tokens: &'a DesignTokens,
+    components: &'a BTreeMap<String, Component>,
+    /// String tables in fallback order, with their normalized locale.
+    locales: Vec<(String, &'a BTreeMap<String, LocalizedString>)>,
+}
+
+impl Context<'_> {
+    /// Replace every `ref` element with its component, recursively.
+    /// `stack` holds the components being expanded, to detect cycles.
+    fn expand_refs(
+        &self,
+        value: &mut Value,
+        path: &str,
+        stack: &mut Vec<String>,
+        errors: &mut Vec<ValidationIssue>,
+    ) {
+        match value {
+            Value::Object(map) if map.get("type").and_then(Value::as_str) == Some("ref") => {
+                let Some((name, mut expanded)) = self.instantiate(map, path, stack, errors) else {
+                    return;
+                };
+                stack.push(name);
+                self.expand_refs(&mut expanded, path, stack, errors);
+                stack.pop();
+                *value = expanded;
+            }
+            Value::Object(map) => {
+                for (k, child) in map.iter_mut() {
+                    if OPAQUE_KEYS.contains(&k.as_str()) {
+                        continue;
+                    }
+                    self.expand_refs(child, &format!("{path}/{}", escape_token(k)), stack, errors);
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.expand_refs(item, &format!("{path}/{i}"), stack, errors);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Build the element for one `ref`: the component body with parameters
+    /// substituted and the ref's own properties laid over its root.
+    fn instantiate(
+        &self,
+        map: &Map<String, Value>,
+        path: &str,
+        stack: &[String],
+        errors: &mut Vec<ValidationIssue>,
+    ) -> Option<(String, Value)> {
+        let Some(name) = map.get("component").and_then(Value::as_str) else {
+            errors.push(issue(path, "`ref` without a `component` name"));
+            return None;
+        };
+        if stack.iter().any(|s| s == name) {
+            errors.push(issue(path, format!("component `{name}` includes itself")));
+            return None;
+        }
+        let Some(component) = self.components.get(name) else {
+            errors.push(issue(
+                format!("{path}/component"),
+                format!("unknown component `{name}`"),
+            ));
+            return None;
+        };
+
+        let mut params = component.params.clone();
+        if let Some(given) = map.get("params").and_then(Value::as_object) {
+            for (key, value) in given {
+                if !params.contains_key(key) {
+                    errors.push(issue(
+                        format!("{path}/params/{}", escape_token(key)),
+                        format!("component `{name}` has no parameter `{key}`"),
+                    ));
+                }
+                params.insert(key.clone(), value.clone());
+            }
+        }
+        if let Some((key, _)) = params.iter().find(|(_, v)| v.is_null()) {
+            errors.push(issue(
+                path,
+                format!("missing required parameter `{key}` of component `{name}`"),
+            ));
+            return None;
+        }
+
+        let mut expanded = component.element.clone();
+        substitute(&mut expanded, &params, name, path, errors);
+        if let Value::Object(root) = &mut expanded {
+            for (key, value) in map {
+                if !matches!(key.as_str(), "type" | "component" | "params") && !value.is_null() {
+                    root.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Some((name.to_string(), expanded))
+    }
+
+    fn node(&self, value: &mut Value, key: &str, path: &str, errors: &mut Vec<ValidationIssue>) {
+        if let Some(reference) = text_ref(value) {
+            if let Some(text) = self.localize(&reference, path, errors) {
+                *value = Value::String(text);
+            }
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                if map.contains_key("type") {
+                    self.apply_style(map, path, errors);
+                    self.apply_font(map, path, errors);
+                }
+                for (k, child) in map.iter_mut() {
+                    if OPAQUE_KEYS.contains(&k.as_str()) {
+                        continue;
+                    }
+                    self.node(child, k, &format!("{path}/{}", escape_token(k)), errors);
+                }
+                if map.get("type").and_then(Value::as_str) == Some("richText") {
+                    expand_markdown(map);
+                }
+            }
+            // Array items inherit the key, so `colors: ["$a", "$b"]` works.
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.node(item, key, &format!("{path}/{i}"), errors);
+                }
+            }
+            Value::String(s) if s.starts_with('$') => {
+                if let Some(resolved) = self.token(key, s, path, errors) {
+                    *value = resolved;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Look up a `{"t": …}` reference along the locale chain and format it.
+    fn localize(
+        &self,
+        reference: &TextRef,
+        path: &str,
+        errors: &mut Vec<ValidationIssue>,
+    ) -> Option<String> {
+        let Some((locale, entry)) = self
+            .locales
+            .iter()
+            .find_map(|(locale, table)| Some((locale, table.get(&reference.key)?)))
+        else {
+            errors.push(issue(path, format!("unknown string `{}`", reference.key)));
+            return None;
+        };
+        let template = match entry {
+            LocalizedString::Text(text) => text,
+            LocalizedString::Plural(forms) => {
+                let Some(count) = reference.args.get("count").and_then(Value::as_f64) else {
+                    errors.push(issue(
+                        path,
+                        format!(
+                            "plural string `{}` needs a numeric `count` argument",
+                            reference.key
+                        ),
+                    ));
+                    return None;
+                };
+                let form = match i18n::plural_category(locale, count) {
+                    _ if count == 0.0 && forms.zero.is_some() => &forms.zero,
+                    "zero" => &forms.zero,
+                    "one" => &forms.one,
+                    "two" => &forms.two,
+                    "few" => &forms.few,
+                    "many" => &forms.many,
+                    _ => &None,
+                };
+                form.as_ref().unwrap_or(&forms.other)
+            }
+        };
+        match i18n::format(template, &reference.args) {
+            Ok(text) => Some(text),
+            Err(arg) => {
+                errors.push(issue(
+                    path,
+                    format!("missing argument `{arg}` for string `{}`", reference.key),
+                ));
+                None
+            }
+        }
+    }
+
+    /// Replace a `"$name"` string under a token-aware key.  Returns `None`
+    /// when the key does not take tokens or the token is unknown.
+    fn token(
+        &self,
+        key: &str,
+        s: &str,
+        path: &str,
+        errors: &mut Vec<ValidationIssue>,
+    ) -> Option<Value> {
+        let name = &s[1..];
+        let kind = if COLOR_KEYS.contains(&key) {
+            "color"
+        } else if SPACING_KEYS.contains(&key) {
+            "spacing"
+        } else if key == "fontSize" {
+            "font"
+        } else {
+            return None;
+        };
+        if let Some(literal) = name.strip_prefix('$') {
+            return Some(Value::String(format!("${literal}")));
+        }
+        let found = match kind {
+            "color" => self
+                .tokens
+                .colors
+                .get(name)
+                .and_then(|c| serde_json::to_value(c).ok()),
+            "spacing" => self.tokens.spacing.get(name).map(|v| Value::from(*v)),
+            _ => self
+                .tokens
+                .fonts
+                .get(name)
+                .and_then(|f| f.size)
+                .map(Value::from),
+        };
+        if found.is_none() {
+            errors.push(issue(path, format!("unknown {kind} token `{s}`")));
+        }
+        found
+    }
+
+    /// Fill properties missing on the element from its named style chain.
+    fn apply_style(
+        &self,
+        map: &mut Map<String, Value>,
+        path: &str,
+        errors: &mut Vec<ValidationIssue>,
+    ) {
+        let name = match map.remove("style") {
+            None | Some(Value::Null) => return,
+            Some(Value::String(name)) => name,
+            Some(_) => {
+                errors.push(issue(
+                    format!("{path}/style"),
+                    "`style` must be a style name",
+                ));
+                return;
+            }
+        };
+        let mut chain: Vec<String> = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next.take() {
+            if chain.contains(&name) {
+                errors.push(issue(
+                    format!("{path}/style"),
+                    format!("style `{name}` extends itself"),
+                ));
+                return;
+            }
+            let Some(Value::Object(props)) = self.styles.get(&name) else {
+                errors.push(issue(
+                    format!("{path}/style"),
+                    format!("unknown style `{name}`"),
+                ));
+                return;
+            };
+            for (k, v) in props {
+                if k == "style" {
+                    next = v.as_str().map(str::to_string);
+                } else if !map.contains_key(k) {
+                    map.insert(k.clone(), v.clone());
+                }
+            }
+            chain.push(name);
+        }
+    }
+
+    /// Expand `"font": "$name"` into `fontSize`/`fontWeight`/`fontDesign`.
+    fn apply_font(
+        &self,
+        map: &mut Map<String, Value>,
+        path: &str,
+        errors: &mut Vec<ValidationIssue>,
+    ) {
+        let Some(font) = map.remove("font") else {
+            return;
+        };
+        let Some(token) = font
+            .as_str()
+            .and_then(|s| s.strip_prefix('$'))
+            .and_then(|name| self.tokens.fonts.get(name))
+        else {
+            errors.push(issue(
+                format!("{path}/font"),
+                format!("unknown font token `{font}`"),
+            ));
+            return;
+        };
+        let fields = [
+            ("fontSize", token.size.map(Value::from)),
+            ("fontWeight", serde_json::to_value(&token.weight).ok()),
+            ("fontDesign", serde_json::to_value(&token.design).ok()),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value.filter(|v| !v.is_null()) {
+                map.entry(key).or_insert(value);
+            }
+        }
+    }
+}
+
+/// A `{"t": "<key>", "args": {…}}` string reference.
+struct TextRef {
+    key: String,
+    args: BTreeMap<String, Value>,
+}
+
+fn text_ref(value: &Value) -> Option<TextRef> {
+    let map = value.as_object()?;
+    let key = map.get("t")?.as_str()?;
+    if !map.keys().all(|k| k == "t" || k == "args") {
+        return None;
+    }
+    let args = match map.get("args") {
+        Some(Value::Object(args)) => args.clone().into_iter().collect(),
+        _ => BTreeMap::new(),
+    };
+    Some(TextRef {
+        key: key.to_string(),
+        args,
+    })
+}
+
+/// Parse the `markdown` of a `richText` element into `spans`, after any
+/// localized string in it was resolved.
+fn expand_markdown(map: &mut Map<String, Value>) {
+    let Some(Value::String(source)) = map.remove("markdown") else {
+        return;
+    };
+    let parsed = crate::markdown::parse(&source)
+        .into_iter()
+        .filter_map(|span| serde_json::to_value(span).ok());
+    match map.get_mut("spans") {
+        Some(Value::Array(spans)) => spans.extend(parsed),
+        _ => {
+            map.insert("spans".into(), Value::Array(parsed.collect()));
+        }
+    }
+}
+
+/// Replace `{{param}}` placeholders in every string of `value`.
+fn substitute(
+    value: &mut Value,
+    params: &BTreeMap<String, Value>,
+    component: &str,
+    path: &str,
+    errors: &mut Vec<ValidationIssue>,
+) {
+    match value {
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|v| substitute(v, params, component, path, errors)),
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|v| substitute(v, params, component, path, errors)),
+        Value::String(s) if s.contains("{{") => {
+            let whole = s
+                .strip_prefix("{{")
+                .and_then(|rest| rest.strip_suffix("}}"))
+                .filter(|name| !name.contains("{{"));
+            if let Some(name) = whole {
+                match params.get(name.trim()) {
+                    Some(param) => *value = param.clone(),
+                    None => errors.push(unknown_param(name.trim(), component, path)),
+                }
+                return;
+            }
+            let mut out = String::with_capacity(s.len());
+            let mut rest = s.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(len) = rest[start..].find("}}") else {
+                    break;
+                };
+                let name = rest[start + 2..start + len].trim();
+                out.push_str(&rest[..start]);
+                match params.get(name) {
+                    Some(Value::String(text)) => out.push_str(text),
+                    Some(other) => out.push_str(&other.to_string()),
+                    None => errors.push(unknown_param(name, component, path)),
+                }
+                rest = &rest[start + len + 2..];
+            }
+            out.push_str(rest);
+            *s = out;
+        }
+        _ => {}
+    }
+}
+
+fn unknown_param(name: &str, component: &str, path: &str) -> ValidationIssue {
+    issue(
+        path,
+        format!("unknown parameter `{name}` in component `{component}` used"),
+    )
+}
+
+/// An issue at `path`, whose message ends with the path.
+fn issue(path: impl Into<String>, what: impl std::fmt::Display) -> ValidationIssue {
+    let path = path.into();
+    ValidationIssue {
+        message: format!("{what} at `{path}`"),
+        path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn action_payloads_are_passed_through() {
+        let payload = json!({ "t": "id", "color": "$x", "size": "$1", "item": { "type": "ref" } });
+        let source = json!({
+            "version": 1,
+            "tokens": { "colors": { "x": "#ff0000" } },
+            "small": { "type": "button", "label": "Go", "action": "go", "payload": payload }
+        });
+        let (_, doc) = resolve(&source, "en").unwrap();
+        assert_eq!(doc["small"]["payload"], payload);
+    }
+}
@@ -0,0 +1,22 @@
+//! JSON Schema for [`WidgetConfig`] and every nested type.
+//!
+//! The schema is derived from the Rust models, so it always matches what
+//! the plugin accepts.  A copy is generated into
+//! `schemas/widget-config.schema.json` at build time and shipped with the
+//! npm package for editor autocompletion:
+//!
+//! ```json
+//! {
+//!   "$schema": "./node_modules/tauri-plugin-widgets-api/schemas/widget-config.schema.json",
+//!   "small": { "type": "text", "content": "Hello" }
+//! }
+//! ```
+
+use schemars::schema::RootSchema;
+
+use crate::models::WidgetConfig;
+
+/// Generate the JSON Schema (draft-07) describing a [`WidgetConfig`].
+pub fn widget_config_schema() -> RootSchema {
+    schemars::schema_for!(WidgetConfig)
+}
@@ -0,0 +1,186 @@
+//! Permission scopes of the plugin's commands.
+//!
+//! Capabilities grant the plugin's commands to windows, and can attach
+//! [`WidgetScope`] entries to them:
+//!
+//! ```json
+//! {
+//!   "identifier": "widgets:allow-set-items",
+//!   "allow": [{ "groups": ["group.com.example.app"], "keys": ["weather_*"] }]
+//! }
+//! ```
+//!
+//! The entries of the command's scope and of the plugin's global scope are
+//! pooled: a call is allowed when it matches one of their `allow` entries
+//! and none of their `deny` entries.  Without any `allow` entries only the
+//! `deny` entries restrict a call, so the `default` permission keeps
+//! granting everything.
+
+use crate::models::WidgetScope;
+
+/// Group item standing for the group of the calling widget window.
+pub const WINDOW_GROUP: &str = "$window";
+
+/// The parts of a command call that scopes restrict.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Access<'a> {
+    pub group: Option<&'a str>,
+    pub key: Option<&'a str>,
+    pub action: Option<&'a str>,
+}
+
+impl std::fmt::Display for Access<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = [
+            ("group", self.group),
+            ("key", self.key),
+            ("action", self.action),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| format!("{name} `{v}`")))
+        .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// Whether `access` matches one of `allows`, or `allows` is empty, and
+/// none of `denies`.  `window_group` is the group of the calling widget
+/// window, if it is one.
+pub fn is_allowed<'a>(
+    allows: impl IntoIterator<Item = &'a WidgetScope>,
+    denies: impl IntoIterator<Item = &'a WidgetScope>,
+    window_group: Option<&str>,
+    access: Access<'_>,
+) -> bool {
+    let mut allows = allows.into_iter().peekable();
+    let allowed = allows.peek().is_none() || allows.any(|e| matches(e, window_group, access, true));
+    allowed
+        && !denies
+            .into_iter()
+            .any(|e| matches(e, window_group, access, false))
+}
+
+/// Whether `entry` covers `access`.  In allow entries, keys and actions
+/// only restrict calls that have them, so one entry can cover both
+/// `set_items` and `get_widget_config`.  Groups are the boundary between
+/// widgets, so a call without one, like a `widget_action` without a group,
+/// only matches allow entries that allow any group.  Deny entries only
+/// match the parts a call has.
+fn matches(
+    entry: &WidgetScope,
+    window_group: Option<&str>,
+    access: Access<'_>,
+    allow: bool,
+) -> bool {
+    let part = |items: &[String], value: Option<&str>, absent: bool| {
+        if items.is_empty() {
+            return true;
+        }
+        let Some(value) = value else {
+            return absent;
+        };
+        items.iter().any(|item| match item.as_str() {
+            WINDOW_GROUP => window_group == Some(value),
+            item => matches_item(item, value),
+        })
+    };
+    part(&entry.groups, access.group, false)
+        && part(&entry.keys, access.key, allow)
+        && part(&entry.actions, access.action, allow)
+}
+
+/// `item` is a name, or a prefix ending in `*`.
+fn matches_item(item: &str, value: &str) -> bool {
+    match item.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => item == value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(groups: &[&str], keys: &[&str], actions: &[&str]) -> WidgetScope {
+        let list = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        WidgetScope {
+            groups: list(groups),
+            keys: list(keys),
+            actions: list(actions),
+        }
+    }
+
+    fn allowed(allows: &[WidgetScope], denies: &[WidgetScope], access: Access<'_>) -> bool {
+        is_allowed(allows, denies, None, access)
+    }
+
+    fn group(group: &str) -> Access<'_> {
+        Access {
+            group: Some(group),
+            ..Access::default()
+        }
+    }
+
+    #[test]
+    fn without_entries_everything_is_allowed() {
+        assert!(allowed(&[], &[], group("g")));
+        assert!(allowed(&[], &[], Access::default()));
+    }
+
+    #[test]
+    fn allow_entries_match_names_and_prefixes() {
+        let allows = [scope(&["group.*"], &["weather_*"], &[])];
+        let key = |key| Access {
+            key: Some(key),
+            ..group("group.a")
+        };
+        assert!(allowed(&allows, &[], key("weather_x")));
+        assert!(allowed(&allows, &[], group("group.a")));
+        assert!(!allowed(&allows, &[], key("secret")));
+        assert!(!allowed(&allows, &[], group("other")));
+    }
+
+    #[test]
+    fn calls_without_group_need_an_entry_allowing_any_group() {
+        let one = [scope(&["g"], &[], &[])];
+        let any = [scope(&[], &[], &["refresh"])];
+        assert!(!allowed(&one, &[], Access::default()));
+        assert!(allowed(&any, &[], Access::default()));
+    }
+
+    #[test]
+    fn one_allow_entry_of_command_or_global_scope_is_enough() {
+        let allows = [scope(&["a"], &[], &[]), scope(&["b"], &[], &[])];
+        assert!(allowed(&allows, &[], group("b")));
+        assert!(!allowed(&allows, &[], group("c")));
+    }
+
+    #[test]
+    fn window_stands_for_the_calling_window_group() {
+        let own = [scope(&[WINDOW_GROUP], &[], &[])];
+        let action = |group| Access {
+            group,
+            action: Some("a"),
+            ..Access::default()
+        };
+        assert!(is_allowed(&own, &[], Some("g"), action(Some("g"))));
+        assert!(!is_allowed(&own, &[], Some("g"), action(Some("h"))));
+        assert!(!is_allowed(&own, &[], None, action(Some("g"))));
+        assert!(!is_allowed(&own, &[], Some("g"), action(None)));
+    }
+
+    #[test]
+    fn deny_entries_only_match_the_parts_a_call_has() {
+        let denies = [scope(&[], &[], &["reset"])];
+        let reset = Access {
+            action: Some("reset"),
+            ..group("g")
+        };
+        assert!(!allowed(&[], &denies, reset));
+        assert!(allowed(&[], &denies, group("g")));
+
+        let own = [scope(&[WINDOW_GROUP], &[], &[])];
+        assert!(!is_allowed(&[], &own, Some("g"), group("g")));
+        assert!(is_allowed(&[], &own, Some("h"), group("g")));
+    }
+}
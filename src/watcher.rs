@@ -0,0 +1,145 @@
+//! Watching group data files for actions queued by native widgets.
+//!
+//! Native widgets cannot call into the app, so they append taps to
+//! `__widget_pending_actions__` in the group's data file.  [`ActionWatcher`]
+//! watches the directories of those files with the platform's file events
+//! (FSEvents, inotify, ReadDirectoryChangesW) and reports the group of every
+//! changed file.  Where the platform watcher cannot be started, e.g. when
+//! the inotify watch limit is reached, it falls back to polling the files.
+//!
+//! Changes are reported on a thread owned by the watcher rather than on the
+//! platform event thread, so the callback may take the store lock and start
+//! watching further groups.  Dropping the watcher stops both threads.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use notify::{Config, Event, EventHandler, EventKind, PollWatcher, RecursiveMode, Watcher};
+
+/// Interval of the polling fallback.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watched data files and the group each belongs to.
+type Files = Arc<Mutex<HashMap<PathBuf, String>>>;
+
+pub(crate) struct ActionWatcher {
+    watcher: Box<dyn Watcher + Send>,
+    forward: Forward,
+    files: Files,
+    /// Watched directories; groups may share one.
+    dirs: HashSet<PathBuf>,
+    polling: bool,
+}
+
+impl ActionWatcher {
+    /// Start a watcher that calls `on_change` with the group of every data
+    /// file that changes.
+    pub fn new(on_change: impl Fn(&str) + Send + 'static) -> notify::Result<Self> {
+        let (tx, rx) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            // Ends when the watcher, and with it every sender, is dropped.
+            for group in rx {
+                on_change(&group);
+            }
+        });
+        let files = Files::default();
+        let forward = Forward {
+            files: files.clone(),
+            tx,
+        };
+        let (watcher, polling) = match notify::recommended_watcher(forward.clone()) {
+            Ok(watcher) => (Box::new(watcher) as Box<dyn Watcher + Send>, false),
+            Err(e) => {
+                log::warn!("file events unavailable, polling widget data instead: {e}");
+                (poll_watcher(forward.clone())?, true)
+            }
+        };
+        Ok(Self {
+            watcher,
+            forward,
+            files,
+            dirs: HashSet::new(),
+            polling,
+        })
+    }
+
+    /// Whether the data file of `group` is watched.
+    pub fn is_watching(&self, group: &str) -> bool {
+        self.files.lock().unwrap().values().any(|g| g == group)
+    }
+
+    /// Watch `file`, the data file of `group`.
+    pub fn watch(&mut self, group: &str, file: &Path) -> notify::Result<()> {
+        // Files are replaced by renames, which end watches on the file
+        // itself, so the directory is watched instead.  Event paths are
+        // canonical on macOS, hence the canonical directory.
+        let dir = file
+            .parent()
+            .map(|dir| dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()))
+            .unwrap_or_default();
+        let file = match file.file_name() {
+            Some(name) => dir.join(name),
+            None => file.to_path_buf(),
+        };
+        if !self.dirs.contains(&dir) {
+            if let Err(e) = self.watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                if self.polling {
+                    return Err(e);
+                }
+                log::warn!("cannot watch {}, polling instead: {e}", dir.display());
+                self.watcher = poll_watcher(self.forward.clone())?;
+                self.polling = true;
+                for dir in &self.dirs {
+                    self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+                }
+                self.watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+            }
+            self.dirs.insert(dir);
+        }
+        self.files.lock().unwrap().insert(file, group.to_string());
+        Ok(())
+    }
+}
+
+fn poll_watcher(forward: Forward) -> notify::Result<Box<dyn Watcher + Send>> {
+    let config = Config::default()
+        .with_poll_interval(POLL_INTERVAL)
+        .with_compare_contents(false);
+    Ok(Box::new(PollWatcher::new(forward, config)?))
+}
+
+/// Passes the groups of changed data files from the platform event thread
+/// to the watcher's own thread.
+#[derive(Clone)]
+struct Forward {
+    files: Files,
+    tx: mpsc::Sender<String>,
+}
+
+impl EventHandler for Forward {
+    fn handle_event(&mut self, event: notify::Result<Event>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                log::warn!("widget data watcher: {e}");
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        let groups: HashSet<String> = {
+            let files = self.files.lock().unwrap();
+            event
+                .paths
+                .iter()
+                .filter_map(|path| files.get(path).cloned())
+                .collect()
+        };
+        for group in groups {
+            let _ = self.tx.send(group);
+        }
+    }
+}
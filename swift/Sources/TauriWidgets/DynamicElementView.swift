@@ -1,4 +1,5 @@
 import SwiftUI
+import WidgetKit
 
 // MARK: - Color helpers
 
@@ -78,6 +79,7 @@ func resolveColor(_ cv: ColorValue?) -> Color? {
 
 public struct DynamicElementView: View {
     public let element: WidgetElement
+    @Environment(\.widgetFamily) private var family
 
     public init(element: WidgetElement) { self.element = element }
 
@@ -92,11 +94,13 @@ public struct DynamicElementView: View {
         case "grid":        renderGrid()
         case "container":   renderContainer()
         case "text":        renderText()
+        case "richText":    renderRichText()
         case "image":       renderImage()
         case "progress":    renderProgress()
         case "gauge":       renderGauge()
         case "button":      renderButton()
         case "toggle":      renderToggle()
+        case "segmented", "picker": renderSegmented()
         case "divider":     renderDivider()
         case "spacer":      renderSpacer()
         case "date":        renderDate()
@@ -170,6 +174,36 @@ public struct DynamicElementView: View {
         if let limit = element.lineLimit { colored.lineLimit(Int(limit)) } else { colored }
     }
 
+    @ViewBuilder private func renderRichText() -> some View {
+        let txt = Text(richTextString())
+        let colored = resolveColor(element.color).map { txt.foregroundColor($0) } ?? txt
+        if let limit = element.lineLimit { colored.lineLimit(Int(limit)) } else { colored }
+    }
+
+    private func richTextString() -> AttributedString {
+        var out = AttributedString()
+        for span in element.spans ?? [] {
+            var part = AttributedString(span.text)
+            let weight = fontWeight(span.fontWeight ?? element.fontWeight)
+            var font: Font
+            if span.fontSize == nil, let ts = textStyleFont(element.textStyle) {
+                font = ts.weight(weight)
+                if span.monospace == true { font = font.monospaced() }
+            } else {
+                font = .system(size: span.fontSize ?? element.fontSize ?? 14, weight: weight,
+                               design: span.monospace == true ? .monospaced : fontDesign(element.fontDesign))
+            }
+            if span.italic == true { font = font.italic() }
+            part.font = font
+            if let c = resolveColor(span.color) { part.foregroundColor = c }
+            if span.strikethrough == true { part.strikethroughStyle = .single }
+            if span.underline == true { part.underlineStyle = .single }
+            if let link = span.link, let url = URL(string: link) { part.link = url }
+            out += part
+        }
+        return out
+    }
+
     // MARK: Image
 
     @ViewBuilder private func renderImage() -> some View {
@@ -253,7 +287,7 @@ public struct DynamicElementView: View {
             .background(resolveColor(element.backgroundColor) ?? Color.accentColor)
             .cornerRadius(element.cornerRadius ?? 8)
         if let act = element.action, !act.isEmpty {
-            Button(intent: WidgetActionIntent(actionName: act)) { btnContent }.buttonStyle(.plain)
+            Button(intent: actionIntent(act, payload: element.payload)) { btnContent }.buttonStyle(.plain)
         } else if let u = element.url, !u.isEmpty, let url = URL(string: u) {
             Link(destination: url) { btnContent }
         } else {
@@ -265,7 +299,7 @@ public struct DynamicElementView: View {
 
     @ViewBuilder private func renderToggle() -> some View {
         let isOn = element.isOn ?? false
-        HStack(spacing: 6) {
+        let content = HStack(spacing: 6) {
             Image(systemName: isOn ? "checkmark.circle.fill" : "circle")
                 .foregroundColor(isOn ? (resolveColor(element.tint) ?? .green) : .gray)
                 .font(.system(size: 18))
@@ -274,6 +308,44 @@ public struct DynamicElementView: View {
                     .foregroundColor(resolveColor(element.color) ?? .primary)
             }
         }
+        if let act = element.action, !act.isEmpty {
+            Button(intent: actionIntent(act, payload: element.payload)) { content }.buttonStyle(.plain)
+        } else {
+            content
+        }
+    }
+
+    // MARK: Segmented
+
+    @ViewBuilder private func renderSegmented() -> some View {
+        let options = element.options ?? []
+        let tint = resolveColor(element.tint) ?? Color.accentColor
+        HStack(spacing: 2) {
+            ForEach(options.indices, id: \.self) { i in
+                let option = options[i]
+                let selected = element.selectedIndex == i
+                let segment = HStack(spacing: 3) {
+                    if let symbol = option.systemName { Image(systemName: symbol) }
+                    Text(option.label).lineLimit(1)
+                }
+                .font(.system(size: element.fontSize ?? 12, weight: selected ? .semibold : .regular))
+                .foregroundColor(selected ? .white : (resolveColor(element.color) ?? .primary))
+                .padding(.horizontal, 8).padding(.vertical, 4)
+                .frame(maxWidth: .infinity)
+                .background(selected ? tint : Color.clear)
+                .cornerRadius(6)
+                .accessibilityAddTraits(selected ? .isSelected : [])
+                if let act = element.action, !act.isEmpty {
+                    Button(intent: actionIntent(act, payload: .string(option.value))) { segment }
+                        .buttonStyle(.plain)
+                } else {
+                    segment
+                }
+            }
+        }
+        .padding(2)
+        .background(tint.opacity(0.15))
+        .cornerRadius(8)
     }
 
     // MARK: Divider / Spacer
@@ -313,8 +385,11 @@ public struct DynamicElementView: View {
     // MARK: Chart
 
     @ViewBuilder private func renderChart() -> some View {
-        let pts = element.chartData ?? []; let maxV = pts.map(\.value).max() ?? 1
-        let tc = resolveColor(element.tint) ?? Color.accentColor
+        // Multi-series charts are drawn as their first series.
+        let first = element.series?.first
+        let pts = element.chartData.flatMap { $0.isEmpty ? nil : $0 } ?? first?.data ?? []
+        let maxV = pts.map(\.value).max() ?? 1
+        let tc = resolveColor(element.tint) ?? resolveColor(first?.color) ?? Color.accentColor
         switch element.chartType {
         case "line":  renderLineChart(pts: pts, maxV: maxV, tc: tc)
         case "area":  renderAreaChart(pts: pts, maxV: maxV, tc: tc)
@@ -341,7 +416,7 @@ public struct DynamicElementView: View {
                         .foregroundColor(resolveColor(element.color) ?? .primary)
                 }
                 if let action = row.action, !action.isEmpty {
-                    Button(intent: WidgetActionIntent(actionName: action)) { rowView }
+                    Button(intent: actionIntent(action, payload: row.payload)) { rowView }
                         .buttonStyle(.plain)
                 } else {
                     rowView
@@ -430,7 +505,7 @@ public struct DynamicElementView: View {
     @ViewBuilder private func renderLink() -> some View {
         let content = VStack(spacing: 0) { renderChildren() }
         if let act = element.action, !act.isEmpty {
-            Button(intent: WidgetActionIntent(actionName: act)) { content }.buttonStyle(.plain)
+            Button(intent: actionIntent(act, payload: element.payload)) { content }.buttonStyle(.plain)
         } else if let u = element.url, !u.isEmpty, let url = URL(string: u) {
             Link(destination: url) { content }
         } else { content }
@@ -565,10 +640,31 @@ public struct DynamicElementView: View {
             .modifier(ClipShapeMod(shape: el.clipShape, cr: el.cornerRadius))
             .modifier(OpacityMod(o: el.opacity))
             .modifier(ShadowMod(s: el.shadow))
+            .modifier(AccessibilityMod(el: el))
     }
 
     // MARK: - Helpers
 
+    /// Intent emitting `action` of this element, tagged with its id and
+    /// the layout family it is shown in.
+    private func actionIntent(_ action: String, payload: ActionPayload?) -> WidgetActionIntent {
+        WidgetActionIntent(actionName: action, payload: payload, elementId: element.id, kind: familyKey)
+    }
+
+    /// Key of the layout family, as in the widget config (`small`, …).
+    private var familyKey: String {
+        switch family {
+        case .systemSmall:          return "small"
+        case .systemMedium:         return "medium"
+        case .systemLarge:          return "large"
+        case .systemExtraLarge:     return "extraLarge"
+        case .accessoryCircular:    return "accessoryCircular"
+        case .accessoryRectangular: return "accessoryRectangular"
+        case .accessoryInline:      return "accessoryInline"
+        default:                    return "medium"
+        }
+    }
+
     private func fontWeight(_ w: String?) -> Font.Weight {
         switch w {
         case "ultralight": return .ultraLight; case "thin": return .thin; case "light": return .light
@@ -710,6 +806,43 @@ private struct FlexMod: ViewModifier {
     }
 }
 
+private struct AccessibilityMod: ViewModifier {
+    let el: WidgetElement
+    func body(content: Content) -> some View {
+        if el.accessibilityHidden == true {
+            content.accessibilityHidden(true)
+        } else if el.accessibilityLabel == nil && el.accessibilityHint == nil && el.accessibilityRole == nil {
+            content
+        } else {
+            content
+                .accessibilityElement(children: el.accessibilityRole == "group" || el.accessibilityLabel != nil ? .combine : .contain)
+                .modifier(OptionalLabel(label: el.accessibilityLabel))
+                .accessibilityHint(el.accessibilityHint.map { Text($0) } ?? Text(""))
+                .accessibilityAddTraits(traits)
+        }
+    }
+
+    private var traits: AccessibilityTraits {
+        switch el.accessibilityRole {
+        case "header": return .isHeader
+        case "button": return .isButton
+        case "link": return .isLink
+        case "image": return .isImage
+        case "text": return .isStaticText
+        case "toggle": return .isToggle
+        case "live": return .updatesFrequently
+        default: return []
+        }
+    }
+}
+
+private struct OptionalLabel: ViewModifier {
+    let label: String?
+    func body(content: Content) -> some View {
+        if let l = label { content.accessibilityLabel(Text(l)) } else { content }
+    }
+}
+
 private struct OpacityMod: ViewModifier {
     let o: Double?
     func body(content: Content) -> some View {
@@ -8,10 +8,18 @@ public struct WidgetUIConfig: Codable {
     public let small: WidgetElement?
     public let medium: WidgetElement?
     public let large: WidgetElement?
+    public let extraLarge: WidgetElement?
+    public let accessoryCircular: WidgetElement?
+    public let accessoryRectangular: WidgetElement?
+    public let accessoryInline: WidgetElement?
 
     public init(version: Int? = nil, small: WidgetElement? = nil,
-                medium: WidgetElement? = nil, large: WidgetElement? = nil) {
+                medium: WidgetElement? = nil, large: WidgetElement? = nil,
+                extraLarge: WidgetElement? = nil, accessoryCircular: WidgetElement? = nil,
+                accessoryRectangular: WidgetElement? = nil, accessoryInline: WidgetElement? = nil) {
         self.version = version; self.small = small; self.medium = medium; self.large = large
+        self.extraLarge = extraLarge; self.accessoryCircular = accessoryCircular
+        self.accessoryRectangular = accessoryRectangular; self.accessoryInline = accessoryInline
     }
 }
 
@@ -40,6 +48,45 @@ public enum ColorValue: Codable {
     private struct AdaptivePair: Codable { let light: String; let dark: String }
 }
 
+// MARK: - Action payload (any JSON value)
+
+public indirect enum ActionPayload: Codable {
+    case string(String)
+    case number(Double)
+    case bool(Bool)
+    case array([ActionPayload])
+    case object([String: ActionPayload])
+    case null
+
+    public init(from decoder: Decoder) throws {
+        let c = try decoder.singleValueContainer()
+        if c.decodeNil() { self = .null }
+        else if let b = try? c.decode(Bool.self) { self = .bool(b) }
+        else if let n = try? c.decode(Double.self) { self = .number(n) }
+        else if let s = try? c.decode(String.self) { self = .string(s) }
+        else if let a = try? c.decode([ActionPayload].self) { self = .array(a) }
+        else { self = .object(try c.decode([String: ActionPayload].self)) }
+    }
+
+    public func encode(to encoder: Encoder) throws {
+        var c = encoder.singleValueContainer()
+        switch self {
+        case .string(let s): try c.encode(s)
+        case .number(let n): try c.encode(n)
+        case .bool(let b): try c.encode(b)
+        case .array(let a): try c.encode(a)
+        case .object(let o): try c.encode(o)
+        case .null: try c.encodeNil()
+        }
+    }
+
+    /// The payload as JSON text, e.g. `{"id":1}` or `"week"`.
+    public var jsonText: String? {
+        guard let data = try? JSONEncoder().encode(self) else { return nil }
+        return String(data: data, encoding: .utf8)
+    }
+}
+
 // MARK: - Element (flat Codable struct)
 
 public struct WidgetElement: Codable {
@@ -64,6 +111,9 @@ public struct WidgetElement: Codable {
     public let textStyle: String?
     public let lineLimit: Int?
 
+    // Rich text
+    public let spans: [TextSpan]?
+
     // Image / Label
     public let systemName: String?
     public let data: String?
@@ -90,6 +140,14 @@ public struct WidgetElement: Codable {
     // Toggle
     public let isOn: Bool?
     public let action: String?
+    public let key: String?
+
+    // Button / Link / Toggle
+    public let payload: ActionPayload?
+
+    // Segmented
+    public let options: [SegmentOption]?
+    public let selectedIndex: Int?
 
     // Divider
     public let thickness: CGFloat?
@@ -104,6 +162,7 @@ public struct WidgetElement: Codable {
     // Chart
     public let chartType: String?
     public let chartData: [ChartDataPoint]?
+    public let series: [ChartSeries]?
     public let items: [ListItem]?
 
     // Shape
@@ -122,6 +181,7 @@ public struct WidgetElement: Codable {
     public let elements: [CanvasDrawCommand]?
 
     // Common style
+    public let id: String?
     public let color: ColorValue?
     public let padding: PaddingValue?
     public let background: BackgroundValue?
@@ -132,6 +192,12 @@ public struct WidgetElement: Codable {
     public let shadow: ShadowConfig?
     public let clipShape: String?
     public let flex: CGFloat?
+
+    // Accessibility
+    public let accessibilityLabel: String?
+    public let accessibilityHint: String?
+    public let accessibilityHidden: Bool?
+    public let accessibilityRole: String?
 }
 
 // MARK: - Supporting types
@@ -239,11 +305,35 @@ public struct ChartDataPoint: Codable {
     public let color: ColorValue?
 }
 
+public struct SegmentOption: Codable {
+    public let label: String
+    public let value: String
+    public let systemName: String?
+}
+
+public struct ChartSeries: Codable {
+    public let name: String?
+    public let data: [ChartDataPoint]
+    public let color: ColorValue?
+}
+
+public struct TextSpan: Codable {
+    public let text: String
+    public let fontWeight: String?
+    public let fontSize: CGFloat?
+    public let italic: Bool?
+    public let strikethrough: Bool?
+    public let underline: Bool?
+    public let monospace: Bool?
+    public let color: ColorValue?
+    public let link: String?
+}
+
 public struct ListItem: Codable {
     public let text: String
     public let checked: Bool?
     public let action: String?
-    public let payload: String?
+    public let payload: ActionPayload?
 }
 
 public struct CanvasDrawCommand: Codable {
@@ -64,11 +64,20 @@ public struct TauriWidgetView: View {
 
     private func layoutForFamily() -> WidgetElement? {
         guard let cfg = entry.config else { return nil }
+        // Same order as `WidgetFamily::fallbacks` in the Rust models.
+        let system = cfg.small ?? cfg.medium ?? cfg.large ?? cfg.extraLarge
         switch entry.family {
-        case .systemSmall:  return cfg.small ?? cfg.medium ?? cfg.large
-        case .systemMedium: return cfg.medium ?? cfg.large ?? cfg.small
-        case .systemLarge:  return cfg.large ?? cfg.medium ?? cfg.small
-        default:            return cfg.medium ?? cfg.small ?? cfg.large
+        case .systemSmall:      return system
+        case .systemMedium:     return cfg.medium ?? cfg.large ?? cfg.small ?? cfg.extraLarge
+        case .systemLarge:      return cfg.large ?? cfg.extraLarge ?? cfg.medium ?? cfg.small
+        case .systemExtraLarge: return cfg.extraLarge ?? cfg.large ?? cfg.medium ?? cfg.small
+        case .accessoryCircular:
+            return cfg.accessoryCircular ?? cfg.accessoryRectangular ?? system
+        case .accessoryRectangular:
+            return cfg.accessoryRectangular ?? cfg.accessoryCircular ?? system
+        case .accessoryInline:
+            return cfg.accessoryInline ?? cfg.accessoryRectangular ?? cfg.accessoryCircular ?? system
+        default:                return cfg.medium ?? system
         }
     }
 
@@ -9,30 +9,147 @@ public enum TauriWidgetsConfig {
 
 /// AppIntent that stores pending actions in the shared container.
 /// The host app polls these and emits Tauri `widget-action` events.
+///
+/// Actions are stored as objects with `action`, `payload`, `elementId`,
+/// `kind` and `timestamp`, the fields of the Rust `WidgetActionEvent`.  A
+/// string payload also selects the matching option of segmented controls
+/// that emit the action, and toggles bound to a store key are flipped, so
+/// the widget shows the tap before the app responds.  The new toggle state
+/// is sent as the payload.
 public struct WidgetActionIntent: AppIntent {
     public static var title: LocalizedStringResource = "Widget Action"
 
     @Parameter(title: "Action")
     public var actionName: String
 
+    @Parameter(title: "Payload")
+    public var payload: String?
+
+    /// Payloads other than strings, as JSON text.
+    @Parameter(title: "Payload JSON")
+    public var payloadJSON: String?
+
+    @Parameter(title: "Element ID")
+    public var elementId: String?
+
+    @Parameter(title: "Kind")
+    public var kind: String?
+
     public init() { self.actionName = "" }
-    public init(actionName: String) { self.actionName = actionName }
+    public init(actionName: String, payload: String? = nil, elementId: String? = nil, kind: String? = nil) {
+        self.actionName = actionName
+        self.payload = payload
+        self.elementId = elementId
+        self.kind = kind
+    }
+    public init(actionName: String, payload: ActionPayload?, elementId: String? = nil, kind: String? = nil) {
+        self.init(actionName: actionName, elementId: elementId, kind: kind)
+        if case .string(let s)? = payload {
+            self.payload = s
+        } else {
+            self.payloadJSON = payload?.jsonText
+        }
+    }
 
     public func perform() async throws -> some IntentResult {
         let group = TauriWidgetsConfig.appGroup
         guard !group.isEmpty else { return .result() }
 
-        var pending: [String] = []
+        var pending: [Any] = []
         if let raw = TauriWidgetDataStore.readValue(forKey: "__widget_pending_actions__", appGroup: group),
            let data = raw.data(using: .utf8),
-           let arr = try? JSONDecoder().decode([String].self, from: data) {
+           let arr = try? JSONSerialization.jsonObject(with: data) as? [Any] {
             pending = arr
         }
-        pending.append(actionName)
-        if let data = try? JSONEncoder().encode(pending),
+        var entry: [String: Any] = [
+            "action": actionName,
+            "nonce": UUID().uuidString,
+            "timestamp": Int64(Date().timeIntervalSince1970 * 1000),
+        ]
+        if let elementId { entry["elementId"] = elementId }
+        if let kind { entry["kind"] = kind }
+        if let isOn = flipToggle(group: group) {
+            entry["payload"] = String(isOn)
+        } else if let payload {
+            entry["payload"] = payload
+            selectOption(value: payload, group: group)
+        } else if let json = payloadJSON?.data(using: .utf8),
+                  let value = try? JSONSerialization.jsonObject(with: json, options: .fragmentsAllowed) {
+            entry["payload"] = value
+        }
+        pending.append(entry)
+        if let data = try? JSONSerialization.data(withJSONObject: pending),
            let str = String(data: data, encoding: .utf8) {
             TauriWidgetDataStore.writeValue(str, forKey: "__widget_pending_actions__", appGroup: group)
         }
         return .result()
     }
+
+    /// Flip the toggle emitting this action that is bound to a store key:
+    /// write the key and `isOn` of every toggle bound to it.  Returns the
+    /// new state, or `nil` when no bound toggle emits the action.
+    private func flipToggle(group: String) -> Bool? {
+        guard let raw = TauriWidgetDataStore.readValue(forKey: "__widget_config__", appGroup: group),
+              let data = raw.data(using: .utf8),
+              let root = try? JSONSerialization.jsonObject(with: data) else { return nil }
+        func find(_ node: Any) -> [String: Any]? {
+            if let obj = node as? [String: Any] {
+                if obj["type"] as? String == "toggle", obj["action"] as? String == actionName, obj["key"] is String {
+                    return obj
+                }
+                return obj.values.lazy.compactMap(find).first
+            }
+            return (node as? [Any])?.lazy.compactMap(find).first
+        }
+        guard let toggle = find(root), let key = toggle["key"] as? String else { return nil }
+        let stored = TauriWidgetDataStore.readValue(forKey: key, appGroup: group)
+        let current = stored.map { $0 == "true" || $0 == "1" } ?? (toggle["isOn"] as? Bool ?? false)
+        let isOn = !current
+        func visit(_ node: Any) -> Any {
+            if var obj = node as? [String: Any] {
+                for (k, child) in obj { obj[k] = visit(child) }
+                if obj["type"] as? String == "toggle", obj["key"] as? String == key { obj["isOn"] = isOn }
+                return obj
+            }
+            if let arr = node as? [Any] { return arr.map(visit) }
+            return node
+        }
+        TauriWidgetDataStore.writeValue(String(isOn), forKey: key, appGroup: group)
+        if let out = try? JSONSerialization.data(withJSONObject: visit(root)),
+           let str = String(data: out, encoding: .utf8) {
+            TauriWidgetDataStore.writeValue(str, forKey: "__widget_config__", appGroup: group)
+        }
+        return isOn
+    }
+
+    /// Move `selectedIndex` of segmented controls emitting this action to
+    /// the option with `value` in the stored config.
+    private func selectOption(value: String, group: String) {
+        guard let raw = TauriWidgetDataStore.readValue(forKey: "__widget_config__", appGroup: group),
+              let data = raw.data(using: .utf8),
+              let root = try? JSONSerialization.jsonObject(with: data) else { return }
+        var changed = false
+        func visit(_ node: Any) -> Any {
+            if var obj = node as? [String: Any] {
+                for (key, child) in obj { obj[key] = visit(child) }
+                let type = obj["type"] as? String
+                if type == "segmented" || type == "picker",
+                   obj["action"] as? String == actionName,
+                   let options = obj["options"] as? [[String: Any]],
+                   let index = options.firstIndex(where: { $0["value"] as? String == value }),
+                   obj["selectedIndex"] as? Int != index {
+                    obj["selectedIndex"] = index
+                    changed = true
+                }
+                return obj
+            }
+            if let arr = node as? [Any] { return arr.map(visit) }
+            return node
+        }
+        let updated = visit(root)
+        guard changed,
+              let out = try? JSONSerialization.data(withJSONObject: updated),
+              let str = String(data: out, encoding: .utf8) else { return }
+        TauriWidgetDataStore.writeValue(str, forKey: "__widget_config__", appGroup: group)
+    }
 }